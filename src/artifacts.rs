@@ -0,0 +1,306 @@
+// Collects what a build produced into `target/<profile>/`, like cargo's `target/`, so
+// the JARs and start scripts are found at the same place whichever backend built them.
+// `artifacts.toml` lists them for other tools.
+
+use serde::Serialize;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::backend::{BuildBackend, classified_jar};
+use crate::binaries;
+use crate::config::Config;
+use crate::error::RsjError;
+use crate::fingerprint::sha256_hex;
+use crate::lock::ResolvedArtifact;
+use crate::utils::{
+    BIN_PATH, printinfo, remove_stale_files, slash_path, write_bytes_if_changed, write_if_changed,
+};
+
+pub const TARGET_PATH: &str = "target";
+pub const ARTIFACTS_FILE: &str = "artifacts.toml";
+const LIB_PATH: &str = "lib";
+const TARGET_MARKER: &str = ".rsj";
+const MARKER_CONTENT: &str = "# Created by rsj. `rsj clean` removes this directory.\n";
+
+const ARTIFACTS_HEADER: &str =
+    "# This file is generated by rsj. Paths are relative to this directory.\n";
+
+#[derive(Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ArtifactKind {
+    Jar,
+    Sources,
+    Javadoc,
+    Dependency,
+    Script,
+}
+
+impl ArtifactKind {
+    fn label(self) -> &'static str {
+        match self {
+            ArtifactKind::Jar => "JAR",
+            ArtifactKind::Sources => "Sources",
+            ArtifactKind::Javadoc => "Javadoc",
+            ArtifactKind::Dependency => "Dependency",
+            ArtifactKind::Script => "Script",
+        }
+    }
+}
+
+/// A file in `target/<profile>/`.
+#[derive(Serialize)]
+pub struct Artifact {
+    pub kind: ArtifactKind,
+    // The binary a start script runs
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub binary: Option<String>,
+    #[serde(serialize_with = "serialize_path")]
+    pub path: PathBuf, // Relative to the profile directory
+    pub sha256: String,
+}
+
+/// The artifacts of one build, as listed in `artifacts.toml`.
+#[derive(Serialize)]
+pub struct Artifacts {
+    #[serde(skip)]
+    pub dir: PathBuf,
+    pub name: String,
+    pub version: String,
+    pub profile: String,
+    pub backend: String,
+    #[serde(rename = "artifact", skip_serializing_if = "Vec::is_empty")]
+    pub artifacts: Vec<Artifact>,
+}
+
+impl Artifacts {
+    /// Absolute path of the project JAR, if the build produced one.
+    pub fn jar(&self) -> Option<PathBuf> {
+        self.paths(ArtifactKind::Jar).next()
+    }
+
+    /// Absolute paths of the artifacts of `kind`.
+    pub fn paths(&self, kind: ArtifactKind) -> impl Iterator<Item = PathBuf> + '_ {
+        self.artifacts
+            .iter()
+            .filter(move |artifact| artifact.kind == kind)
+            .map(|artifact| self.dir.join(&artifact.path))
+    }
+
+    /// Print where each artifact ended up, leaving out the dependencies.
+    pub fn print(&self) {
+        printinfo(&format!(
+            "Build succeeded! Artifacts are in {}",
+            shown(&self.dir).display()
+        ));
+        for artifact in &self.artifacts {
+            if artifact.kind != ArtifactKind::Dependency {
+                println!(
+                    "  {:<10} {}",
+                    artifact.kind.label(),
+                    shown(&self.dir.join(&artifact.path)).display()
+                );
+            }
+        }
+        let dependencies = self.paths(ArtifactKind::Dependency).count();
+        if dependencies > 0 {
+            println!(
+                "  {:<10} {} in {}",
+                "Libraries",
+                dependencies,
+                shown(&self.dir.join(LIB_PATH)).display()
+            );
+        }
+    }
+
+    // Copy `source` to `path` in the profile directory
+    fn add_copy(
+        &mut self,
+        source: &Path,
+        path: PathBuf,
+        kind: ArtifactKind,
+    ) -> Result<(), RsjError> {
+        let content = fs::read(source)
+            .map_err(|e| RsjError::io(format!("Failed to read `{}`", source.display()), e))?;
+        let destination = self.dir.join(&path);
+        if let Some(parent) = destination.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| RsjError::io("Failed to create the target directory", e))?;
+        }
+        write_bytes_if_changed(&destination, &content)?;
+        self.artifacts.push(Artifact {
+            kind,
+            binary: None,
+            path,
+            sha256: sha256_hex(&content),
+        });
+        Ok(())
+    }
+
+    // Record a file already written to the profile directory
+    fn add(
+        &mut self,
+        written: &Path,
+        binary: Option<String>,
+        kind: ArtifactKind,
+    ) -> Result<(), RsjError> {
+        let content = fs::read(written)
+            .map_err(|e| RsjError::io(format!("Failed to read `{}`", written.display()), e))?;
+        let path = written
+            .strip_prefix(&self.dir)
+            .unwrap_or(written)
+            .to_path_buf();
+        self.artifacts.push(Artifact {
+            kind,
+            binary,
+            path,
+            sha256: sha256_hex(&content),
+        });
+        Ok(())
+    }
+
+    fn paths_of_all(&self) -> impl Iterator<Item = PathBuf> + '_ {
+        self.artifacts
+            .iter()
+            .map(|artifact| self.dir.join(&artifact.path))
+    }
+}
+
+/// `target/<profile>` next to rsj.toml.
+pub fn profile_dir(config: &Config) -> PathBuf {
+    config
+        .manifest_dir
+        .join(TARGET_PATH)
+        .join(&config.profile.name)
+}
+
+/// Copy the JARs of the last build into `target/<profile>/`, write start scripts
+/// for them and list everything in `artifacts.toml`. Thin JARs get their runtime
+/// dependencies in `lib/`. Files of earlier builds are removed.
+pub fn collect(
+    config: &Config,
+    backend: &dyn BuildBackend,
+    temp_path: &Path,
+) -> Result<Artifacts, RsjError> {
+    let target_dir = config.manifest_dir.join(TARGET_PATH);
+    let dir = profile_dir(config);
+    // Only a `target/` rsj creates gets the marker `rsj clean` looks for
+    let created = !target_dir.exists();
+    fs::create_dir_all(&dir)
+        .map_err(|e| RsjError::io("Failed to create the target directory", e))?;
+    if created {
+        write_if_changed(&target_dir.join(TARGET_MARKER), MARKER_CONTENT)?;
+    }
+    let ignore = target_dir.join(".gitignore");
+    if !ignore.exists() {
+        write_if_changed(&ignore, "*\n")?;
+    }
+
+    let mut artifacts = Artifacts {
+        dir: dir.clone(),
+        name: config.project.name.clone(),
+        version: config.project.version.clone(),
+        profile: config.profile.name.clone(),
+        backend: backend.name().to_string(),
+        artifacts: Vec::new(),
+    };
+
+    // Without a JAR there is nothing to run, which `rsj run` reports
+    let jar_path = backend.jar_path(config, temp_path);
+    if jar_path.exists() {
+        artifacts.add_copy(&jar_path, file_name(&jar_path), ArtifactKind::Jar)?;
+        for (enabled, classifier, kind) in [
+            (config.sources_jar(), "sources", ArtifactKind::Sources),
+            (config.javadoc_jar(), "javadoc", ArtifactKind::Javadoc),
+        ] {
+            let path = classified_jar(&jar_path, classifier);
+            if enabled && path.exists() {
+                artifacts.add_copy(&path, file_name(&path), kind)?;
+            }
+        }
+        if !config.use_shadow() {
+            let dependencies = backend.resolved_artifacts(config, temp_path)?;
+            for (source, path) in lib_paths(&dependencies) {
+                artifacts.add_copy(&source, path, ArtifactKind::Dependency)?;
+            }
+        }
+
+        // Scripts find the JARs relative to themselves, so the directory can be moved
+        let class_path: Vec<PathBuf> = artifacts
+            .artifacts
+            .iter()
+            .filter(|artifact| {
+                matches!(artifact.kind, ArtifactKind::Jar | ArtifactKind::Dependency)
+            })
+            .map(|artifact| artifact.path.clone())
+            .collect();
+        for (binary, script) in
+            binaries::write_start_scripts(config, &dir.join(BIN_PATH), &class_path)?
+        {
+            artifacts.add(&script, Some(binary), ArtifactKind::Script)?;
+        }
+    }
+
+    let manifest = dir.join(ARTIFACTS_FILE);
+    let content = toml::to_string(&artifacts).map_err(|e| {
+        RsjError::Project(format!("Failed to write `{}`: {}", manifest.display(), e))
+    })?;
+    write_if_changed(&manifest, &format!("{}{}", ARTIFACTS_HEADER, content))?;
+
+    let mut keep: HashSet<PathBuf> = artifacts.paths_of_all().collect();
+    keep.insert(manifest);
+    remove_stale_files(&dir, &keep)?;
+    Ok(artifacts)
+}
+
+/// Where each runtime dependency of a thin JAR goes, relative to the JAR, without
+/// duplicates. The javac backend names the same paths in the JAR's `Class-Path`.
+pub fn lib_paths(dependencies: &[ResolvedArtifact]) -> Vec<(PathBuf, PathBuf)> {
+    let mut seen = HashSet::new();
+    let dependencies: Vec<&ResolvedArtifact> = dependencies
+        .iter()
+        .filter(|dependency| seen.insert(&dependency.path))
+        .collect();
+    dependencies
+        .iter()
+        .map(|dependency| {
+            // Artifacts of different groups may share a file name, e.g. `core-1.0.jar`
+            let name = file_name(&dependency.path);
+            let shared = dependencies
+                .iter()
+                .any(|other| other.path != dependency.path && file_name(&other.path) == name);
+            let name = if shared {
+                PathBuf::from(format!("{}-{}", dependency.group, name.display()))
+            } else {
+                name
+            };
+            (dependency.path.clone(), Path::new(LIB_PATH).join(name))
+        })
+        .collect()
+}
+
+/// Remove `target/` if rsj created it, returning whether it did.
+pub fn clean(config: &Config) -> Result<bool, RsjError> {
+    let target_dir = config.manifest_dir.join(TARGET_PATH);
+    // Another tool's `target/` has no marker
+    if !target_dir.join(TARGET_MARKER).is_file() {
+        return Ok(false);
+    }
+    fs::remove_dir_all(&target_dir)
+        .map_err(|e| RsjError::io("Failed to clean the target directory", e))?;
+    Ok(true)
+}
+
+// `target/dev` rather than `./target/dev` when rsj runs next to rsj.toml
+fn shown(path: &Path) -> &Path {
+    path.strip_prefix(".").unwrap_or(path)
+}
+
+fn file_name(path: &Path) -> PathBuf {
+    PathBuf::from(path.file_name().unwrap_or_default())
+}
+
+// `/`-separated on every platform, so the file reads the same everywhere
+fn serialize_path<S: serde::Serializer>(path: &Path, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&slash_path(path))
+}
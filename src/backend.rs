@@ -0,0 +1,237 @@
+use std::collections::HashSet;
+use std::io::{BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use crate::build::project_root;
+use crate::build_info;
+use crate::config::{Backend, Config};
+use crate::diagnostics::{DiagnosticRenderer, SourceMap};
+use crate::error::RsjError;
+use crate::gradle::GradleBackend;
+use crate::javac::JavacBackend;
+use crate::lock::{Pin, ResolvedArtifact};
+use crate::maven::MavenBackend;
+use crate::resources::{RESOURCES_PATH, TEST_RESOURCES_PATH, copy_resources};
+use crate::utils::{copy_src_files, is_inline_test, remove_stale_files};
+
+/// A build tool rsj can drive. Every backend turns the same `rsj.toml` and `src/`
+/// into a runnable JAR, so `build`, `run` and `clean` behave the same whichever is used.
+pub trait BuildBackend {
+    /// Name shown in messages, e.g. `gradle`.
+    fn name(&self) -> &'static str;
+
+    /// Generate the backend's project below `temp_path` from `src_dir` and `tests_dir`,
+    /// resolving dependencies to the versions in `pins`.
+    fn prepare(
+        &self,
+        config: &Config,
+        src_dir: &Path,
+        tests_dir: &Path,
+        temp_path: &Path,
+        pins: &[Pin],
+    ) -> Result<(), RsjError>;
+
+    /// Build the JAR, skipping the work if nothing changed since the last build.
+    fn build(&self, config: &Config, temp_path: &Path) -> Result<(), RsjError>;
+
+    /// Path of the JAR produced by `build`.
+    fn jar_path(&self, config: &Config, temp_path: &Path) -> PathBuf;
+
+    /// The runtime classpath the last `build` resolved, for `rsj.lock`.
+    fn resolved_artifacts(
+        &self,
+        config: &Config,
+        temp_path: &Path,
+    ) -> Result<Vec<ResolvedArtifact>, RsjError>;
+
+    /// Directory the backend writes JUnit XML reports to, `None` if it cannot run tests.
+    fn test_results_dir(&self, _temp_path: &Path) -> Option<PathBuf> {
+        None
+    }
+
+    /// Run the tests whose name contains `filter`, showing their output if `nocapture`.
+    fn run_tests(
+        &self,
+        _config: &Config,
+        _temp_path: &Path,
+        _filter: Option<&str>,
+        _nocapture: bool,
+    ) -> Result<(), RsjError> {
+        Err(unsupported_tests(self.name()))
+    }
+}
+
+/// The backend selected by `backend` in rsj.toml.
+pub fn backend_for(config: &Config) -> Box<dyn BuildBackend> {
+    match config.backend() {
+        Backend::Gradle => Box::new(GradleBackend),
+        Backend::Javac => Box::new(JavacBackend),
+        Backend::Maven => Box::new(MavenBackend),
+    }
+}
+
+pub fn unsupported_tests(backend: &str) -> RsjError {
+    RsjError::Config(format!(
+        "`rsj test` needs JUnit and is not supported by the {} backend.",
+        backend
+    ))
+}
+
+// `<name>-<version>-<classifier>.jar`, which every backend writes next to the main JAR
+pub fn classified_jar(jar_path: &Path, classifier: &str) -> PathBuf {
+    let stem = jar_path.file_stem().unwrap().to_string_lossy();
+    jar_path.with_file_name(format!("{}-{}.jar", stem, classifier))
+}
+
+// Path of the base namespace package inside a Maven-style project
+pub fn java_source_path(base_namespace: &str) -> String {
+    format!("src/main/java/{}", base_namespace.replace(".", "/"))
+}
+
+// Path of the base namespace package inside a Maven-style test source set
+pub fn java_test_path(base_namespace: &str) -> String {
+    format!("src/test/java/{}", base_namespace.replace(".", "/"))
+}
+
+/// Copy `src/`, `tests/`, `resources/` and `test-resources/` into the Maven-style
+/// layout shared by Gradle and Maven, dropping copies of files that no longer exist.
+pub fn copy_standard_layout(
+    config: &Config,
+    src_dir: &Path,
+    tests_dir: &Path,
+    project_dir: &Path,
+) -> Result<(), RsjError> {
+    let namespace = &config.project.base_namespace;
+    let inline_tests = config.inline_tests();
+    let src = src_dir.to_str().unwrap();
+
+    // Copy source files with correct namespace
+    let namespace_dir = project_dir.join(java_source_path(namespace));
+    let mut main_files: HashSet<PathBuf> =
+        copy_src_files(src, &namespace_dir, namespace, |path| {
+            !(inline_tests && is_inline_test(path))
+        })?
+        .into_iter()
+        .collect();
+    main_files.extend(build_info::write_build_info(config, &namespace_dir)?);
+    remove_stale_files(&project_dir.join("src/main/java"), &main_files)?;
+
+    // Copy `tests/`, and inline test classes from `src/` if enabled
+    let test_java_dir = project_dir.join(java_test_path(namespace));
+    let mut test_files = HashSet::new();
+    if tests_dir.exists() {
+        test_files.extend(copy_src_files(
+            tests_dir.to_str().unwrap(),
+            &test_java_dir,
+            namespace,
+            |_| true,
+        )?);
+    }
+    if inline_tests {
+        test_files.extend(copy_src_files(
+            src,
+            &test_java_dir,
+            namespace,
+            is_inline_test,
+        )?);
+    }
+    remove_stale_files(&project_dir.join("src/test/java"), &test_files)?;
+
+    // Resources keep their paths, they are not tied to the namespace
+    let root = project_root(config);
+    for (resources_path, target) in [
+        (RESOURCES_PATH, "src/main/resources"),
+        (TEST_RESOURCES_PATH, "src/test/resources"),
+    ] {
+        let target = project_dir.join(target);
+        let written = copy_resources(config, &root.join(resources_path), &target)?;
+        remove_stale_files(&target, &written.into_iter().collect())?;
+    }
+
+    Ok(())
+}
+
+/// Maps the standard layout inside `project_dir` back to `src/` and `tests/`, and
+/// inline tests back to `src/`.
+pub fn standard_source_map(config: &Config, project_dir: &Path) -> SourceMap {
+    let root = project_root(config);
+    let namespace = &config.project.base_namespace;
+    let test_root = project_dir.join(java_test_path(namespace));
+    let source_map = SourceMap::new(project_dir)
+        .with_root(
+            &project_dir.join(java_source_path(namespace)),
+            &root.join("src"),
+        )
+        .with_root(&test_root, &root.join("tests"));
+    if config.inline_tests() {
+        source_map.with_root(&test_root, &root.join("src"))
+    } else {
+        source_map
+    }
+}
+
+/// Which output stream of a build tool carries the compiler diagnostics.
+pub enum DiagnosticStream {
+    Stdout,
+    Stderr,
+}
+
+/// Run a build tool in `dir`, rendering the compiler diagnostics it prints with
+/// paths translated through `source_map`. Output is shown line by line as it comes,
+/// and the other stream stays attached to the terminal, so progress is visible live.
+pub fn run_build_tool(
+    program: &str,
+    args: &[&str],
+    dir: &Path,
+    source_map: &SourceMap,
+    stream: DiagnosticStream,
+) -> Result<(), RsjError> {
+    let mut command = Command::new(program);
+    command.args(args).current_dir(dir).stdin(Stdio::inherit());
+    match stream {
+        DiagnosticStream::Stdout => command.stdout(Stdio::piped()).stderr(Stdio::inherit()),
+        DiagnosticStream::Stderr => command.stdout(Stdio::inherit()).stderr(Stdio::piped()),
+    };
+
+    let mut child = command
+        .spawn()
+        .map_err(|e| RsjError::tool_not_found(program, e))?;
+    let captured: Box<dyn Read> = match stream {
+        DiagnosticStream::Stdout => Box::new(child.stdout.take().unwrap()),
+        DiagnosticStream::Stderr => Box::new(child.stderr.take().unwrap()),
+    };
+    let show = |text: &str| match stream {
+        DiagnosticStream::Stdout => print!("{}", text),
+        DiagnosticStream::Stderr => eprint!("{}", text),
+    };
+
+    let mut renderer = DiagnosticRenderer::new(source_map);
+    let mut reader = BufReader::new(captured);
+    let mut line = Vec::new();
+    loop {
+        line.clear();
+        let read = reader
+            .read_until(b'\n', &mut line)
+            .map_err(|e| RsjError::io(format!("Failed to read the output of `{}`", program), e))?;
+        if read == 0 {
+            break;
+        }
+        let text = String::from_utf8_lossy(&line);
+        show(&renderer.line(text.trim_end_matches(['\n', '\r'])));
+    }
+    let (rendered, errors) = renderer.finish();
+    show(&rendered);
+
+    let status = child
+        .wait()
+        .map_err(|e| RsjError::io(format!("Failed to wait for `{}`", program), e))?;
+    if !status.success() {
+        return Err(RsjError::BuildFailed {
+            status: status.code(),
+            errors,
+        });
+    }
+
+    Ok(())
+}
@@ -0,0 +1,147 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::build::project_root;
+use crate::config::{BinTarget, Config};
+use crate::error::RsjError;
+use crate::utils::{slash_path, write_if_changed};
+
+/// Every entry point of the project: `main_class`, if any, named after the project, the
+/// `[[bin]]` entries, then one per `src/bin/*.java` named after its file.
+pub fn binaries(config: &Config) -> Vec<BinTarget> {
+    let mut binaries: Vec<BinTarget> = config
+        .project
+        .main_class
+        .iter()
+        .map(|main_class| BinTarget {
+            name: config.project.name.clone(),
+            main_class: main_class.clone(),
+        })
+        .collect();
+    binaries.extend(config.bin.iter().cloned());
+
+    // `src/bin/Tool.java` becomes `<base_namespace>.bin.Tool`, unless `[[bin]]` already
+    // lists it or uses its name
+    let bin_dir = project_root(config).join("src").join("bin");
+    let mut discovered: Vec<BinTarget> = fs::read_dir(bin_dir)
+        .into_iter()
+        .flatten()
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "java"))
+        .filter_map(|path| {
+            let name = path.file_stem()?.to_str()?.to_string();
+            Some(BinTarget {
+                main_class: format!("bin.{}", name),
+                name,
+            })
+        })
+        .filter(|found| {
+            !binaries
+                .iter()
+                .any(|binary| binary.name == found.name || binary.main_class == found.main_class)
+        })
+        .collect();
+    discovered.sort_by(|a, b| a.name.cmp(&b.name));
+    binaries.extend(discovered);
+    binaries
+}
+
+/// The binary `rsj run --bin <name>` selects, or else the first one.
+pub fn find_binary(config: &Config, name: Option<&str>) -> Result<BinTarget, RsjError> {
+    let mut binaries = binaries(config);
+    if binaries.is_empty() {
+        return Err(RsjError::Config(format!(
+            "Error: `{}` has nothing to run. Set `main_class` or add a `[[bin]]` entry.",
+            config.project.name
+        )));
+    }
+    let Some(name) = name else {
+        return Ok(binaries.swap_remove(0));
+    };
+
+    match binaries.iter().position(|binary| binary.name == name) {
+        Some(index) => Ok(binaries.swap_remove(index)),
+        None => {
+            let names: Vec<&str> = binaries.iter().map(|binary| binary.name.as_str()).collect();
+            Err(RsjError::Config(format!(
+                "Error: No binary named `{}`. Available binaries: {}",
+                name,
+                names.join(", ")
+            )))
+        }
+    }
+}
+
+/// Fully qualified name of a binary's main class.
+pub fn qualified_main_class(config: &Config, binary: &BinTarget) -> String {
+    format!("{}.{}", config.project.base_namespace, binary.main_class)
+}
+
+/// Write a start script per binary to `bin_dir`, running it on `class_path`, which is
+/// relative to the parent of `bin_dir`. Returns each binary's name with its scripts.
+pub fn write_start_scripts(
+    config: &Config,
+    bin_dir: &Path,
+    class_path: &[PathBuf],
+) -> Result<Vec<(String, PathBuf)>, RsjError> {
+    fs::create_dir_all(bin_dir)
+        .map_err(|e| RsjError::io("Failed to create the start script directory", e))?;
+
+    // Scripts look up the JARs from their own location, so they work from anywhere
+    let shell_paths: Vec<String> = class_path
+        .iter()
+        .map(|path| format!("\"$APP_HOME\"/{}", shell_quote(&slash_path(path))))
+        .collect();
+    let batch_paths: Vec<String> = class_path
+        .iter()
+        .map(|path| format!("%APP_HOME%\\{}", slash_path(path).replace('/', "\\")))
+        .collect();
+    let mut written = Vec::new();
+    for binary in binaries(config) {
+        let main_class = qualified_main_class(config, &binary);
+
+        let script = bin_dir.join(&binary.name);
+        write_if_changed(
+            &script,
+            &format!(
+                "#!/bin/sh\n# Generated by rsj\nAPP_HOME=$(cd \"$(dirname \"$0\")/..\" && pwd)\nexec java $JAVA_OPTS -cp {} {} \"$@\"\n",
+                shell_paths.join(":"),
+                main_class
+            ),
+        )?;
+        make_executable(&script)?;
+
+        let batch = bin_dir.join(format!("{}.bat", binary.name));
+        write_if_changed(
+            &batch,
+            &format!(
+                "@rem Generated by rsj\r\n@echo off\r\nset \"APP_HOME=%~dp0..\"\r\njava %JAVA_OPTS% -cp \"{}\" {} %*\r\n",
+                batch_paths.join(";"),
+                main_class
+            ),
+        )?;
+
+        written.push((binary.name.clone(), script));
+        written.push((binary.name, batch));
+    }
+    Ok(written)
+}
+
+// Quote for sh, where nothing inside single quotes is special except the quote itself
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+#[cfg(unix)]
+fn make_executable(path: &Path) -> Result<(), RsjError> {
+    use std::os::unix::fs::PermissionsExt;
+    fs::set_permissions(path, fs::Permissions::from_mode(0o755))
+        .map_err(|e| RsjError::io(format!("Failed to make `{}` executable", path.display()), e))
+}
+
+// Windows runs `.bat` files without a permission bit
+#[cfg(not(unix))]
+fn make_executable(_path: &Path) -> Result<(), RsjError> {
+    Ok(())
+}
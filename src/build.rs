@@ -1,110 +1,93 @@
-use std::fs::{self, File};
-use std::io::Write;
-use std::path::{Path, PathBuf};
-use std::process::Command;
-
-use crate::config::load_config;
-use crate::gradle::setup_gradle_project;
-use crate::utils::{GRADLE_PATH, OUTPUT_PATH, printinfo, separator};
-
-// Helper to create a file with error mapping
-fn create_file(path: &Path, content: &str) -> Result<(), String> {
-    fs::write(path, content).map_err(|e| format!("Failed to write to `{}`: {}", path.display(), e))
-}
-
-// Helper to create a directory with error mapping
-fn create_directory(path: &Path) -> Result<(), String> {
-    fs::create_dir(path).map_err(|e| format!("Failed to create `{}`: {}", path.display(), e))
-}
-
-pub fn init_project() -> Result<(), String> {
-    // Check if project files already exist
-    let config_path = Path::new("rsj.toml");
-    let src_dir = Path::new("src");
-
-    if config_path.exists() {
-        return Err("Error: `rsj.toml` already exists.".to_string());
+use std::fs;
+use std::path::PathBuf;
+
+use crate::artifacts::{self, ArtifactKind, Artifacts};
+use crate::backend::{BuildBackend, backend_for};
+use crate::config::Config;
+use crate::error::RsjError;
+use crate::fingerprint;
+use crate::lock::{self, LockMode};
+use crate::utils::{FINGERPRINT_PATH, OUTPUT_PATH, printinfo, separator, write_if_changed};
+
+/// Build the project and collect its artifacts into `target/<profile>/`.
+pub fn build_project(config: &Config, lock_mode: &LockMode) -> Result<Artifacts, RsjError> {
+    let (backend, temp_path) = prepare_project(config, lock_mode)?;
+
+    // `rsj update` has to resolve again even if nothing else changed
+    if matches!(lock_mode, LockMode::Update(_)) {
+        fingerprint::clear_all(&temp_path.join(FINGERPRINT_PATH));
     }
 
-    if src_dir.exists() {
-        return Err("Error: `src` directory already exists.".to_string());
-    }
-
-    // Create config file
-    let config_content = r#"[project]
-name = "my_project"
-version = "0.1.0"
-main_class = "Main"
-base_namespace = "com.example"
-
-# [dependencies]
-# junit = "org.junit.jupiter:junit-jupiter:5.9.1"
-"#;
-    create_file(config_path, config_content)?;
-
-    // Create src directory
-    create_directory(src_dir)?;
+    // Run the build
+    backend.build(config, &temp_path)?;
+    lock::record(
+        config,
+        lock_mode,
+        &backend.resolved_artifacts(config, &temp_path)?,
+    )?;
+    let artifacts = artifacts::collect(config, backend.as_ref(), &temp_path)?;
 
-    // Create sample Java files
-    create_java_sample_files(src_dir)?;
+    separator();
 
-    printinfo("Initialized a new RSJ project with Gradle.");
-    Ok(())
+    artifacts.print();
+    Ok(artifacts)
 }
 
-fn create_java_sample_files(src_dir: &Path) -> Result<(), String> {
-    // Main.java
-    let main_content = r#"package com.example;
-
-import com.example.classone.ClassOne;
-import com.example.classtwo.ClassTwo;
-
-public class Main {
-    public static void main(String[] args) {
-        ClassOne.oneMethod();
-        ClassTwo.twoMethod();
-    }
-}"#;
-    create_file(&src_dir.join("Main.java"), main_content)?;
-
-    // ClassOne.java
-    let classone_dir = src_dir.join("classone");
-    create_directory(&classone_dir)?;
-    let classone_content = r#"package com.example.classone;
-
-public class ClassOne {
-    public static void oneMethod() {
-        System.out.println("ClassOne method");
-    }
-}"#;
-    create_file(&classone_dir.join("ClassOne.java"), classone_content)?;
+/// The classpath binaries run on: the project JAR in `target/<profile>/`, plus the
+/// runtime dependencies next to it unless they are shaded into it.
+pub fn runtime_class_path(
+    config: &Config,
+    artifacts: &Artifacts,
+) -> Result<Vec<PathBuf>, RsjError> {
+    let Some(jar_path) = artifacts.jar() else {
+        let backend = backend_for(config);
+        return Err(RsjError::MissingJar(
+            backend.jar_path(config, &build_dir(config)),
+        ));
+    };
 
-    // ClassTwo.java
-    let classtwo_dir = src_dir.join("classtwo");
-    create_directory(&classtwo_dir)?;
-    let classtwo_content = r#"package com.example.classtwo;
+    // Binaries may run in another directory
+    std::iter::once(jar_path)
+        .chain(artifacts.paths(ArtifactKind::Dependency))
+        .map(|path| {
+            path.canonicalize()
+                .map_err(|e| RsjError::io(format!("Failed to read `{}`", path.display()), e))
+        })
+        .collect()
+}
 
-public class ClassTwo {
-    public static void twoMethod() {
-        System.out.println("ClassTwo method");
+// Directory holding `src` and `tests`
+pub fn project_root(config: &Config) -> PathBuf {
+    match &config.project.root_path {
+        Some(root_path) => config.manifest_dir.join(root_path),
+        None => config.manifest_dir.clone(),
     }
-}"#;
-    create_file(&classtwo_dir.join("ClassTwo.java"), classtwo_content)?;
-
-    Ok(())
 }
 
-pub fn build_project() -> Result<(), String> {
-    let config = load_config()?;
+// Directory the backends generate their projects in, next to `rsj.toml`
+pub fn build_dir(config: &Config) -> PathBuf {
+    config.manifest_dir.join(OUTPUT_PATH)
+}
 
+// Generate the project of the configured backend, ready to build or test
+pub fn prepare_project(
+    config: &Config,
+    lock_mode: &LockMode,
+) -> Result<(Box<dyn BuildBackend>, PathBuf), RsjError> {
     // Verify src directory exists
-    let src_dir = Path::new(config.project.root_path.as_deref().unwrap_or(".")).join("src");
+    let root = project_root(config);
+    let src_dir = root.join("src");
     if !src_dir.exists() {
-        return Err("Error: `src` directory is missing.".to_string());
+        return Err(RsjError::Project(
+            "Error: `src` directory is missing.".to_string(),
+        ));
     }
 
+    // Check rsj.lock before generating anything
+    let pins = lock::pins(config, lock_mode)?;
+
     // Create and prepare build directory
-    let temp_path = prepare_build_directory()?;
+    let temp_path = prepare_build_directory(config)?;
 
     printinfo(&format!(
         "Using temporary build directory: {}",
@@ -113,76 +96,36 @@ pub fn build_project() -> Result<(), String> {
 
     separator();
 
-    // Setup Gradle project
-    setup_gradle_project(&config, src_dir.to_str().unwrap(), &temp_path)?;
-
-    // Run Gradle build
-    run_gradle_build(&config, &temp_path)?;
-
-    separator();
+    // Setup the backend's project
+    let backend = backend_for(config);
+    backend.prepare(config, &src_dir, &root.join("tests"), &temp_path, &pins)?;
 
-    printinfo("Build succeeded! Output is in the temporary directory.");
-    Ok(())
+    Ok((backend, temp_path))
 }
 
-fn prepare_build_directory() -> Result<PathBuf, String> {
-    let temp_path = Path::new(OUTPUT_PATH).to_path_buf();
+fn prepare_build_directory(config: &Config) -> Result<PathBuf, RsjError> {
+    let temp_path = build_dir(config);
 
     // Create build directory
     fs::create_dir_all(&temp_path)
-        .map_err(|_| "Failed to create temporary build directory.".to_string())?;
+        .map_err(|e| RsjError::io("Failed to create temporary build directory", e))?;
 
     // Create .gitignore file
-    let mut gitignore_file = File::create(temp_path.join(".gitignore"))
-        .map_err(|_| "Failed to create `.gitignore`.".to_string())?;
-
-    writeln!(gitignore_file, "*\n").map_err(|_| "Failed to write to `.gitignore`.".to_string())?;
+    write_if_changed(&temp_path.join(".gitignore"), "*\n\n")?;
 
     Ok(temp_path)
 }
 
-fn run_gradle_build(config: &crate::config::Config, temp_path: &Path) -> Result<(), String> {
-    // Define the Gradle project directory
-    let gradle_project_dir = temp_path.join(GRADLE_PATH);
-
-    // Determine build parameters
-    let use_shadow = config.project.use_shadow.unwrap_or(true);
-    let task = if use_shadow { "shadowJar" } else { "build" };
-
-    // Check for Gradle wrapper
-    let gradlew_path = gradle_project_dir.join("gradlew");
-    let wrapper_jar_path = gradle_project_dir
-        .join("gradle")
-        .join("wrapper")
-        .join("gradle-wrapper.jar");
-
-    // Select the appropriate Gradle command
-    let (program, args) = if gradlew_path.exists() && wrapper_jar_path.exists() {
-        ("./gradlew", vec![task])
-    } else {
-        ("gradle", vec![task])
-    };
-
-    // Run the build
-    let build_status = Command::new(program)
-        .args(&args)
-        .current_dir(&gradle_project_dir)
-        .status()
-        .map_err(|_| "Failed to run Gradle build.".to_string())?;
-
-    if !build_status.success() {
-        return Err("Build failed.".to_string());
-    }
-
-    Ok(())
-}
-
-pub fn clean_build() -> Result<(), String> {
-    let output_path = Path::new(OUTPUT_PATH);
+pub fn clean_build(config: &Config) -> Result<(), RsjError> {
+    let output_path = build_dir(config);
 
+    let mut cleaned = artifacts::clean(config)?;
     if output_path.exists() {
-        fs::remove_dir_all(output_path)
-            .map_err(|e| format!("Failed to clean the build output: {}", e))?;
+        fs::remove_dir_all(&output_path)
+            .map_err(|e| RsjError::io("Failed to clean the build output", e))?;
+        cleaned = true;
+    }
+    if cleaned {
         printinfo("Build output cleaned.");
     } else {
         printinfo("Build output not found.");
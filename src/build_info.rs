@@ -0,0 +1,219 @@
+// `[build_info]` generates a class describing the build, so programs can report
+// their version at runtime, much like `env!` and the `built` crate do for Rust.
+
+use regex::Regex;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::artifacts::TARGET_PATH;
+use crate::config::{BuildInfoConfig, Config};
+use crate::error::RsjError;
+use crate::lock::LOCK_PATH;
+use crate::utils::{OUTPUT_PATH, write_if_changed};
+
+pub const DEFAULT_CLASS: &str = "BuildInfo";
+
+/// The class name of `[build_info]`, relative to `base_namespace`.
+pub fn class_name(build_info: &BuildInfoConfig) -> &str {
+    build_info.class.as_deref().unwrap_or(DEFAULT_CLASS)
+}
+
+/// Write the `[build_info]` class below `namespace_dir`, the directory of
+/// `base_namespace`. Returns the written file, if the project asks for one.
+pub fn write_build_info(
+    config: &Config,
+    namespace_dir: &Path,
+) -> Result<Option<PathBuf>, RsjError> {
+    let Some(build_info) = &config.build_info else {
+        return Ok(None);
+    };
+    let class = class_name(build_info);
+    let relative = format!("{}.java", class.replace('.', "/"));
+
+    let (package, simple_name) = match class.rsplit_once('.') {
+        Some((subpackage, simple_name)) => (
+            format!("{}.{}", config.project.base_namespace, subpackage),
+            simple_name,
+        ),
+        None => (config.project.base_namespace.clone(), class),
+    };
+    let path = namespace_dir.join(&relative);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| RsjError::io("Failed to create package directory", e))?;
+    }
+
+    let commit = git_output(config, &["rev-parse", "HEAD"]);
+    // Files rsj writes itself, e.g. the rsj.lock of the first build, do not count
+    let dirty = commit.is_some()
+        && git_output(
+            config,
+            &[
+                "status",
+                "--porcelain",
+                "--",
+                ".",
+                &format!(":(exclude){}", LOCK_PATH),
+                &format!(":(exclude){}", OUTPUT_PATH),
+                &format!(":(exclude){}", TARGET_PATH),
+            ],
+        )
+        .is_some_and(|s| !s.is_empty());
+    let render = |timestamp: &str| {
+        render_class(
+            config,
+            build_info,
+            &package,
+            simple_name,
+            commit.as_deref(),
+            dirty,
+            timestamp,
+        )
+    };
+
+    let content = match source_date_epoch()? {
+        Some(seconds) => render(&format_timestamp(seconds)),
+        None => {
+            // Keep the previous timestamp while nothing else changed, otherwise
+            // every build would recompile the project
+            let existing = fs::read_to_string(&path).unwrap_or_default();
+            let timestamp = Regex::new(r#"BUILD_TIMESTAMP = "([^"]*)""#).unwrap();
+            match timestamp.captures(&existing) {
+                Some(captures) if render(&captures[1]) == existing => existing,
+                _ => {
+                    let now = SystemTime::now()
+                        .duration_since(UNIX_EPOCH)
+                        .map(|duration| duration.as_secs() as i64)
+                        .unwrap_or_default();
+                    render(&format_timestamp(now))
+                }
+            }
+        }
+    };
+    write_if_changed(&path, &content)?;
+    Ok(Some(path))
+}
+
+fn render_class(
+    config: &Config,
+    build_info: &BuildInfoConfig,
+    package: &str,
+    simple_name: &str,
+    commit: Option<&str>,
+    dirty: bool,
+    timestamp: &str,
+) -> String {
+    let commit = match commit {
+        Some(commit) => string_literal(commit),
+        None => "null".to_string(),
+    };
+    let features = build_info
+        .features
+        .iter()
+        .map(|feature| string_literal(feature))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    // Java 8 compatible, `java_version` may be as low as that
+    format!(
+        r#"package {package};
+
+import java.util.Arrays;
+import java.util.Collections;
+import java.util.List;
+
+/** Describes this build. Generated by rsj from the [build_info] section of rsj.toml, do not edit. */
+public final class {simple_name} {{
+    public static final String NAME = {name};
+    public static final String VERSION = {version};
+    /** The commit built from, or {{@code null}} outside a git repository. */
+    public static final String GIT_COMMIT = {commit};
+    /** Whether the work tree had uncommitted changes. */
+    public static final boolean GIT_DIRTY = {dirty};
+    /** UTC, in ISO-8601. {{@code SOURCE_DATE_EPOCH}} overrides it. */
+    public static final String BUILD_TIMESTAMP = "{timestamp}";
+    public static final List<String> FEATURES = Collections.unmodifiableList(Arrays.<String>asList({features}));
+
+    private {simple_name}() {{}}
+}}
+"#,
+        name = string_literal(&config.project.name),
+        version = string_literal(&config.project.version),
+    )
+}
+
+// A Java string literal holding `value`
+fn string_literal(value: &str) -> String {
+    let mut literal = String::from("\"");
+    for c in value.chars() {
+        match c {
+            '"' => literal.push_str("\\\""),
+            '\\' => literal.push_str("\\\\"),
+            '\n' => literal.push_str("\\n"),
+            '\r' => literal.push_str("\\r"),
+            '\t' => literal.push_str("\\t"),
+            c if c.is_control() => literal.push_str(&format!("\\u{:04x}", c as u32)),
+            c => literal.push(c),
+        }
+    }
+    literal.push('"');
+    literal
+}
+
+// The trimmed output of a successful git command in the project, if git is there
+fn git_output(config: &Config, args: &[&str]) -> Option<String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(&config.manifest_dir)
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+// Seconds since the epoch from `SOURCE_DATE_EPOCH`, for reproducible builds
+fn source_date_epoch() -> Result<Option<i64>, RsjError> {
+    match env::var("SOURCE_DATE_EPOCH") {
+        Ok(value) => value.trim().parse().map(Some).map_err(|_| {
+            RsjError::Config(format!(
+                "Error: `SOURCE_DATE_EPOCH` must be a number of seconds, got `{}`.",
+                value
+            ))
+        }),
+        Err(_) => Ok(None),
+    }
+}
+
+// `1700000000` becomes `2023-11-14T22:13:20Z`
+fn format_timestamp(seconds: i64) -> String {
+    let days = seconds.div_euclid(86_400);
+    let time = seconds.rem_euclid(86_400);
+
+    // Civil date from days since 1970-01-01, after Howard Hinnant's algorithm
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        time / 3600,
+        time % 3600 / 60,
+        time % 60
+    )
+}
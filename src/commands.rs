@@ -1,13 +1,122 @@
-use clap::Subcommand;
+use clap::{Args, Subcommand, ValueEnum};
+use std::path::PathBuf;
+
+use crate::config::{DEV_PROFILE, RELEASE_PROFILE};
 
 #[derive(Subcommand)]
 pub enum Commands {
     /// Build the Java project
-    Build,
+    Build {
+        /// Fail if `rsj.lock` is missing or would change
+        #[clap(long)]
+        locked: bool,
+        #[clap(flatten)]
+        profile: ProfileOptions,
+    },
     /// Run the Java project
-    Run,
+    Run {
+        /// Fail if `rsj.lock` is missing or would change
+        #[clap(long)]
+        locked: bool,
+        #[clap(flatten)]
+        profile: ProfileOptions,
+        /// Run this binary instead of `main_class`
+        #[clap(long, value_name = "NAME")]
+        bin: Option<String>,
+        /// Extra JVM option, after the `jvm_args` of `[run]` (repeatable)
+        #[clap(long = "jvm-arg", value_name = "ARG", allow_hyphen_values = true)]
+        jvm_args: Vec<String>,
+        /// Arguments passed to the program
+        #[clap(last = true)]
+        args: Vec<String>,
+    },
+    /// Run the tests in `tests/`
+    Test {
+        /// Only run tests whose name contains this string
+        filter: Option<String>,
+        /// Show the output of the tests
+        #[clap(long)]
+        nocapture: bool,
+        /// Fail if `rsj.lock` is missing or does not match `[dependencies]`
+        #[clap(long)]
+        locked: bool,
+    },
+    /// Resolve dependencies again and update `rsj.lock`
+    Update {
+        /// Only update this dependency from `[dependencies]`
+        #[clap(short, long)]
+        package: Option<String>,
+    },
     /// Clean the build output
     Clean,
-    /// Initialize a RSJ project
-    Init,
+    /// Create a new RSJ project in a new directory
+    New {
+        /// Directory to create, also the default project name
+        path: PathBuf,
+        #[clap(flatten)]
+        options: InitOptions,
+    },
+    /// Initialize a RSJ project in the current directory
+    Init {
+        #[clap(flatten)]
+        options: InitOptions,
+    },
+}
+
+/// Selects the build profile of `rsj build` and `rsj run`.
+#[derive(Args)]
+pub struct ProfileOptions {
+    /// Build with the release profile
+    #[clap(long, conflicts_with = "profile")]
+    pub release: bool,
+    /// Build with the profile NAME, `dev` by default
+    #[clap(long, value_name = "NAME")]
+    pub profile: Option<String>,
+}
+
+impl ProfileOptions {
+    pub fn name(&self) -> &str {
+        if self.release {
+            RELEASE_PROFILE
+        } else {
+            self.profile.as_deref().unwrap_or(DEV_PROFILE)
+        }
+    }
+}
+
+/// Options shared by `rsj new` and `rsj init`.
+#[derive(Args)]
+pub struct InitOptions {
+    /// Create a library, from the `lib` template
+    #[clap(long, conflicts_with_all = ["bin", "template"])]
+    pub lib: bool,
+    /// Create an application (the default)
+    #[clap(long)]
+    pub bin: bool,
+    /// Template to start from: `cli`, `lib`, `picocli`, `javafx`, `http`, a
+    /// directory or a git repository URL
+    #[clap(long, value_name = "TEMPLATE")]
+    pub template: Option<String>,
+    /// Project name, the directory name by default
+    #[clap(long)]
+    pub name: Option<String>,
+    /// Base namespace, derived from `git config user.email` by default
+    #[clap(long)]
+    pub namespace: Option<String>,
+    /// Main class relative to the namespace, `Main` by default
+    #[clap(long)]
+    pub main_class: Option<String>,
+    /// Java release to compile for, e.g. `21`
+    #[clap(long)]
+    pub java_version: Option<String>,
+    /// Version control system to initialize
+    #[clap(long, value_enum, default_value_t = Vcs::Git)]
+    pub vcs: Vcs,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Vcs {
+    /// Run `git init`, unless already inside a repository
+    Git,
+    None,
 }
@@ -1,52 +1,606 @@
+use indexmap::IndexMap;
 use serde::Deserialize;
-use std::collections::HashMap;
+use std::env;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+
+use crate::error::RsjError;
+use crate::validation::{self, Validator};
+
+pub const MANIFEST_NAME: &str = "rsj.toml";
 
 #[derive(Deserialize)]
 pub struct Config {
     pub project: Project,
-    pub dependencies: Option<HashMap<String, String>>,
+    pub dependencies: Option<IndexMap<String, Dependency>>,
+    #[serde(rename = "dev-dependencies")]
+    pub dev_dependencies: Option<IndexMap<String, Dependency>>, // Only available to tests
+    #[serde(rename = "build-dependencies")]
+    pub build_dependencies: Option<IndexMap<String, Dependency>>, // Annotation processors
+    pub test: Option<TestConfig>,
+    pub run: Option<RunConfig>,
+    pub lib: Option<LibConfig>, // Present for library projects
+    pub resources: Option<ResourcesConfig>,
+    pub build_info: Option<BuildInfoConfig>,
+    #[serde(default)]
+    pub bin: Vec<BinTarget>, // Entry points besides `main_class`, from `[[bin]]`
+    #[serde(skip)]
+    pub manifest_dir: PathBuf, // Directory of rsj.toml, every other path is relative to it
+    #[serde(default, rename = "profile")]
+    pub profiles: IndexMap<String, ProfileConfig>, // `[profile.<name>]`
+    #[serde(skip)]
+    pub profile: Profile, // Selected on the command line
 }
 
 #[derive(Deserialize)]
 pub struct Project {
     pub name: String,
     pub version: String,
-    pub main_class: String,
-    pub base_namespace: String,         // Base namespace for the project
-    pub root_path: Option<String>,      // Path to the project root
+    pub main_class: Option<String>, // Required unless the project is a library
+    pub base_namespace: String,     // Base namespace for the project
+    pub java_version: Option<String>, // Java release to compile for, e.g. `17`
+    pub vendor: Option<String>,     // JDK vendor for `java_version`, e.g. `adoptium`
+    pub root_path: Option<String>,  // Path to the project root, relative to rsj.toml
     pub gradle_version: Option<String>, // Optional Gradle distribution version
+    pub gradle_distribution_url: Option<String>, // Wrapper distribution, a URL or a local zip
+    pub gradle_distribution_sha256: Option<String>, // Checksum the wrapper verifies
     pub shadow_plugin_version: Option<String>, // Optional Shadow plugin version
-    pub use_shadow: Option<bool>,       // Whether to apply the ShadowJar plugin
+    pub use_shadow: Option<bool>,   // Whether to apply the ShadowJar plugin
+    pub backend: Option<Backend>,   // Build tool used to compile the project
+    pub local_repository: Option<String>, // Extra local Maven repository, relative to rsj.toml
+}
+
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Backend {
+    #[default]
+    Gradle,
+    Javac,
+    Maven,
+}
+
+#[derive(Deserialize)]
+pub struct TestConfig {
+    pub inline: Option<bool>, // Treat `src/**/*Test.java` files as tests
+}
+
+/// Marks a library: a plain JAR without `Main-Class`, shipped with sources and
+/// Javadoc JARs.
+#[derive(Deserialize)]
+pub struct LibConfig {
+    pub sources: Option<bool>, // Build `<name>-<version>-sources.jar`, on by default
+    pub javadoc: Option<bool>, // Build `<name>-<version>-javadoc.jar`, on by default
+}
+
+/// How `resources/` and `test-resources/` are copied.
+#[derive(Deserialize)]
+pub struct ResourcesConfig {
+    #[serde(default)]
+    pub filter: Vec<String>, // Globs of files whose `${...}` placeholders are replaced
+    #[serde(default)]
+    pub properties: IndexMap<String, String>, // Values for `${key}` besides `project.*`
+}
+
+/// The generated class describing the build, see `build_info`.
+#[derive(Deserialize)]
+pub struct BuildInfoConfig {
+    pub class: Option<String>, // Relative to `base_namespace`, `BuildInfo` by default
+    #[serde(default)]
+    pub features: Vec<String>, // Listed in `FEATURES`, e.g. what this build was made with
+}
+
+/// An entry point of the project. `main_class` is relative to `base_namespace`.
+#[derive(Deserialize, Clone)]
+pub struct BinTarget {
+    pub name: String,
+    pub main_class: String,
+}
+
+/// How `rsj run` starts the JVM.
+#[derive(Deserialize, Default)]
+pub struct RunConfig {
+    #[serde(default)]
+    pub jvm_args: Vec<String>,
+    #[serde(default)]
+    pub system_properties: IndexMap<String, String>, // Passed as `-Dkey=value`
+    #[serde(default)]
+    pub env: IndexMap<String, String>,
+    pub working_dir: Option<String>, // Relative to rsj.toml, defaults to the current directory
+}
+
+/// Settings of `[profile.<name>]`. Custom profiles start from the one they inherit.
+#[derive(Deserialize, Default)]
+pub struct ProfileConfig {
+    pub inherits: Option<String>, // Required for profiles other than `dev` and `release`
+    pub debug: Option<bool>,      // Debug information, `-g`
+    pub lint: Option<Vec<String>>, // Warnings to enable, `-Xlint:<keys>`
+    pub werror: Option<bool>,     // Fail on warnings, `-Werror`
+    pub parameters: Option<bool>, // Keep parameter names for reflection, `-parameters`
+    pub sources: Option<bool>,    // Build a sources JAR, overriding `[lib]`
+    pub javadoc: Option<bool>,    // Build a Javadoc JAR, overriding `[lib]`
+    pub jvm_args: Option<Vec<String>>, // Passed by `rsj run` after `[run] jvm_args`
+}
+
+pub const DEV_PROFILE: &str = "dev";
+pub const RELEASE_PROFILE: &str = "release";
+
+/// The selected build profile with its `[profile.<name>]` settings applied, `dev`
+/// unless `--release` or `--profile` choose another. Release builds leave out
+/// debug information.
+#[derive(Clone)]
+pub struct Profile {
+    pub name: String,
+    pub debug: bool,
+    pub lint: Vec<String>,
+    pub werror: bool,
+    pub parameters: bool,
+    pub sources: Option<bool>,
+    pub javadoc: Option<bool>,
+    pub jvm_args: Vec<String>,
 }
 
-pub fn load_config() -> Result<Config, String> {
-    let config_path = "rsj.toml";
+impl Default for Profile {
+    fn default() -> Self {
+        Profile::builtin(DEV_PROFILE)
+    }
+}
 
-    if !Path::new(config_path).exists() {
-        return Err(format!(
-            "Error: Missing `{}` file. Run 'rsj init' to create a new project.",
-            config_path
-        ));
+impl Profile {
+    fn builtin(name: &str) -> Profile {
+        Profile {
+            name: name.to_string(),
+            debug: name == DEV_PROFILE,
+            lint: Vec::new(),
+            werror: false,
+            parameters: false,
+            sources: None,
+            javadoc: None,
+            jvm_args: Vec::new(),
+        }
     }
 
-    // Load config file content
-    let config_content = fs::read_to_string(config_path)
-        .map_err(|e| format!("Failed to read `{}`: {}", config_path, e))?;
+    fn apply(&mut self, settings: &ProfileConfig) {
+        if let Some(debug) = settings.debug {
+            self.debug = debug;
+        }
+        if let Some(lint) = &settings.lint {
+            self.lint = lint.clone();
+        }
+        if let Some(werror) = settings.werror {
+            self.werror = werror;
+        }
+        if let Some(parameters) = settings.parameters {
+            self.parameters = parameters;
+        }
+        self.sources = settings.sources.or(self.sources);
+        self.javadoc = settings.javadoc.or(self.javadoc);
+        if let Some(jvm_args) = &settings.jvm_args {
+            self.jvm_args = jvm_args.clone();
+        }
+    }
+
+    /// javac options of the profile besides debug information, which each backend
+    /// sets its own way.
+    pub fn javac_args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        if !self.lint.is_empty() {
+            args.push(format!("-Xlint:{}", self.lint.join(",")));
+        }
+        if self.werror {
+            args.push("-Werror".to_string());
+        }
+        if self.parameters {
+            args.push("-parameters".to_string());
+        }
+        args
+    }
+}
+
+impl Config {
+    pub fn backend(&self) -> Backend {
+        self.project.backend.unwrap_or_default()
+    }
+
+    /// The entries of one dependency table, in the order rsj.toml declares them.
+    pub fn dependency_table(&self, kind: DependencyKind) -> Vec<(&String, &Dependency)> {
+        let table = match kind {
+            DependencyKind::Normal => &self.dependencies,
+            DependencyKind::Dev => &self.dev_dependencies,
+            DependencyKind::Build => &self.build_dependencies,
+        };
+        table.iter().flatten().collect()
+    }
+
+    pub fn is_library(&self) -> bool {
+        self.lib.is_some()
+    }
+
+    /// Whether dependencies are shaded into the JAR. Libraries leave them to their
+    /// consumers.
+    pub fn use_shadow(&self) -> bool {
+        !self.is_library() && self.project.use_shadow.unwrap_or(true)
+    }
+
+    /// Whether to build a sources JAR. Libraries ship one unless the profile says
+    /// otherwise.
+    pub fn sources_jar(&self) -> bool {
+        self.profile.sources.unwrap_or_else(|| {
+            self.lib
+                .as_ref()
+                .is_some_and(|lib| lib.sources.unwrap_or(true))
+        })
+    }
+
+    /// Whether to build a Javadoc JAR. Libraries ship one unless the profile says
+    /// otherwise.
+    pub fn javadoc_jar(&self) -> bool {
+        self.profile.javadoc.unwrap_or_else(|| {
+            self.lib
+                .as_ref()
+                .is_some_and(|lib| lib.javadoc.unwrap_or(true))
+        })
+    }
+
+    /// Select the profile `name`, applying the settings of the profiles it
+    /// inherits from first.
+    pub fn select_profile(&mut self, name: &str) -> Result<(), RsjError> {
+        let mut chain = vec![name];
+        let mut current = name;
+        while current != DEV_PROFILE && current != RELEASE_PROFILE {
+            // `inherits` and cycles are checked when rsj.toml is loaded
+            let parent = self
+                .profiles
+                .get(current)
+                .and_then(|settings| settings.inherits.as_deref())
+                .filter(|parent| !chain.contains(parent));
+            match parent {
+                Some(parent) => {
+                    chain.push(parent);
+                    current = parent;
+                }
+                None => {
+                    let mut names = vec![DEV_PROFILE, RELEASE_PROFILE];
+                    names.extend(
+                        self.profiles
+                            .keys()
+                            .map(String::as_str)
+                            .filter(|name| *name != DEV_PROFILE && *name != RELEASE_PROFILE),
+                    );
+                    return Err(RsjError::Config(format!(
+                        "Error: Profile `{}` is not defined. Use one of {}, or add `[profile.{}]` with `inherits`.",
+                        name,
+                        names.join(", "),
+                        name
+                    )));
+                }
+            }
+        }
+
+        let mut profile = Profile::builtin(current);
+        for name in chain.iter().rev() {
+            if let Some(settings) = self.profiles.get(*name) {
+                profile.apply(settings);
+            }
+        }
+        profile.name = name.to_string();
+        self.profile = profile;
+        Ok(())
+    }
+
+    /// The fully qualified `main_class`, if the project has one.
+    pub fn main_class(&self) -> Option<String> {
+        self.project
+            .main_class
+            .as_ref()
+            .map(|main_class| format!("{}.{}", self.project.base_namespace, main_class))
+    }
+
+    pub fn inline_tests(&self) -> bool {
+        self.test
+            .as_ref()
+            .and_then(|test| test.inline)
+            .unwrap_or(false)
+    }
+}
+
+/// Which dependency table of rsj.toml an entry comes from.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum DependencyKind {
+    Normal, // [dependencies]
+    Dev,    // [dev-dependencies]
+    Build,  // [build-dependencies]
+}
+
+impl DependencyKind {
+    pub const ALL: [DependencyKind; 3] = [
+        DependencyKind::Normal,
+        DependencyKind::Dev,
+        DependencyKind::Build,
+    ];
+
+    /// The name of the table in rsj.toml.
+    pub fn table_name(self) -> &'static str {
+        match self {
+            DependencyKind::Normal => "dependencies",
+            DependencyKind::Dev => "dev-dependencies",
+            DependencyKind::Build => "build-dependencies",
+        }
+    }
+}
+
+/// An entry of a dependency table, either `"group:artifact:version"` or a table.
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub enum Dependency {
+    Simple(String),
+    Detailed(DetailedDependency),
+}
+
+#[derive(Deserialize)]
+pub struct DetailedDependency {
+    pub group: Option<String>,
+    pub artifact: Option<String>, // Defaults to the name of the entry
+    pub version: Option<String>,
+    pub classifier: Option<String>,
+    pub scope: Option<Scope>,
+    #[serde(default)]
+    pub exclude: Vec<String>, // `group` or `group:artifact` of transitive dependencies to drop
+    #[serde(default)]
+    pub optional: bool, // Not passed on to projects that depend on this one
+}
+
+/// Where a dependency is visible, named after the Gradle configurations.
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Scope {
+    #[default]
+    #[serde(rename = "implementation")]
+    Implementation,
+    #[serde(rename = "api")]
+    Api,
+    #[serde(rename = "compileOnly", alias = "provided")]
+    CompileOnly,
+    #[serde(rename = "runtimeOnly", alias = "runtime")]
+    RuntimeOnly,
+}
+
+impl Scope {
+    /// The name used in rsj.toml, which is also the Gradle configuration.
+    pub fn name(self) -> &'static str {
+        match self {
+            Scope::Implementation => "implementation",
+            Scope::Api => "api",
+            Scope::CompileOnly => "compileOnly",
+            Scope::RuntimeOnly => "runtimeOnly",
+        }
+    }
+
+    pub fn on_compile_classpath(self) -> bool {
+        self != Scope::RuntimeOnly
+    }
+
+    pub fn on_runtime_classpath(self) -> bool {
+        self != Scope::CompileOnly
+    }
+}
+
+/// Resolved `group:artifact:version[:classifier]` of a dependency.
+pub struct Coordinates {
+    pub group: String,
+    pub artifact: String,
+    pub version: String,
+    pub classifier: Option<String>,
+}
+
+impl Coordinates {
+    /// The `group:artifact:version[:classifier]` notation.
+    pub fn notation(&self) -> String {
+        match &self.classifier {
+            Some(classifier) => format!(
+                "{}:{}:{}:{}",
+                self.group, self.artifact, self.version, classifier
+            ),
+            None => format!("{}:{}:{}", self.group, self.artifact, self.version),
+        }
+    }
+}
+
+/// A transitive dependency to leave out, by group or by group and artifact.
+pub struct Exclusion {
+    pub group: String,
+    pub artifact: Option<String>,
+}
+
+impl Dependency {
+    pub fn coordinates(&self, name: &str) -> Result<Coordinates, RsjError> {
+        let detailed = match self {
+            Dependency::Simple(coordinates) => return parse_coordinates(name, coordinates),
+            Dependency::Detailed(detailed) => detailed,
+        };
+
+        let missing = |field: &str| {
+            RsjError::Config(format!("Dependency `{}` is missing `{}`.", name, field))
+        };
+        Ok(Coordinates {
+            group: detailed.group.clone().ok_or_else(|| missing("group"))?,
+            artifact: detailed
+                .artifact
+                .clone()
+                .unwrap_or_else(|| name.to_string()),
+            version: detailed.version.clone().ok_or_else(|| missing("version"))?,
+            classifier: detailed.classifier.clone(),
+        })
+    }
 
-    // Parse TOML content into Config struct
-    let config: Config = toml::from_str(&config_content)
-        .map_err(|e| format!("Invalid TOML format in `{}`: {}", config_path, e))?;
+    pub fn scope(&self) -> Scope {
+        match self {
+            Dependency::Simple(_) => Scope::default(),
+            Dependency::Detailed(detailed) => detailed.scope.unwrap_or_default(),
+        }
+    }
 
-    // Basic validation
-    if config.project.name.trim().is_empty() {
-        return Err("Project name cannot be empty in rsj.toml".to_string());
+    pub fn optional(&self) -> bool {
+        matches!(self, Dependency::Detailed(detailed) if detailed.optional)
     }
 
-    if config.project.main_class.trim().is_empty() {
-        return Err("Main class name cannot be empty in rsj.toml".to_string());
+    pub fn exclusions(&self, name: &str) -> Result<Vec<Exclusion>, RsjError> {
+        let Dependency::Detailed(detailed) = self else {
+            return Ok(Vec::new());
+        };
+
+        detailed
+            .exclude
+            .iter()
+            .map(
+                |exclude| match exclude.split(':').collect::<Vec<_>>().as_slice() {
+                    [group] => Ok(Exclusion {
+                        group: group.to_string(),
+                        artifact: None,
+                    }),
+                    [group, artifact] => Ok(Exclusion {
+                        group: group.to_string(),
+                        artifact: Some(artifact.to_string()),
+                    }),
+                    _ => Err(RsjError::Config(format!(
+                        "Dependency `{}` excludes `{}`, expected `group` or `group:artifact`.",
+                        name, exclude
+                    ))),
+                },
+            )
+            .collect()
+    }
+
+    /// A stable one-line summary, so rsj.lock notices any edit to the entry.
+    pub fn describe(&self) -> String {
+        let detailed = match self {
+            Dependency::Simple(coordinates) => return coordinates.clone(),
+            Dependency::Detailed(detailed) => detailed,
+        };
+
+        let field = |value: &Option<String>| value.clone().unwrap_or_default();
+        let mut description = format!(
+            "{}:{}:{}",
+            field(&detailed.group),
+            field(&detailed.artifact),
+            field(&detailed.version)
+        );
+        if let Some(classifier) = &detailed.classifier {
+            description.push_str(&format!(":{}", classifier));
+        }
+        if let Some(scope) = detailed.scope {
+            description.push_str(&format!(" scope={}", scope.name()));
+        }
+        if !detailed.exclude.is_empty() {
+            description.push_str(&format!(" exclude={}", detailed.exclude.join(",")));
+        }
+        if detailed.optional {
+            description.push_str(" optional");
+        }
+        description
+    }
+}
+
+fn parse_coordinates(name: &str, coordinates: &str) -> Result<Coordinates, RsjError> {
+    let parts: Vec<&str> = coordinates.split(':').collect();
+    let (group, artifact, version, classifier) = match parts.as_slice() {
+        [group, artifact, version] => (*group, *artifact, *version, None),
+        [group, artifact, version, classifier] => (*group, *artifact, *version, Some(*classifier)),
+        _ => {
+            return Err(RsjError::Config(format!(
+                "Dependency `{}` must be `group:artifact:version`, found `{}`.",
+                name, coordinates
+            )));
+        }
+    };
+    Ok(Coordinates {
+        group: group.to_string(),
+        artifact: artifact.to_string(),
+        version: version.to_string(),
+        classifier: classifier.map(str::to_string),
+    })
+}
+
+/// Load the manifest at `manifest_path`, or else the `rsj.toml` of the current
+/// directory or the nearest parent directory that has one.
+pub fn load_config(manifest_path: Option<&Path>) -> Result<Config, RsjError> {
+    let config_path = match manifest_path {
+        Some(path) if !path.is_file() => {
+            return Err(RsjError::Config(format!(
+                "Error: Manifest `{}` does not exist.",
+                path.display()
+            )));
+        }
+        Some(path) => path.to_path_buf(),
+        None => find_manifest()?,
+    };
+
+    // Load config file content
+    let config_content = fs::read_to_string(&config_path)
+        .map_err(|e| RsjError::io(format!("Failed to read `{}`", config_path.display()), e))?;
+
+    // Parse TOML content into Config struct, noting keys rsj does not know
+    let mut validator = Validator::new(&config_path, &config_content);
+    let mut unused_keys = Vec::new();
+    let parsed: Result<Config, toml::de::Error> =
+        serde_ignored::deserialize(toml::Deserializer::new(&config_content), |path| {
+            unused_keys.push(validation::key_path(&path))
+        });
+    let mut config = match parsed {
+        Ok(config) => config,
+        Err(e) => {
+            validator.error_at(e.span(), e.message());
+            validator.report();
+            return Err(RsjError::Config(format!(
+                "Invalid TOML format in `{}`.",
+                config_path.display()
+            )));
+        }
+    };
+    config.manifest_dir = match config_path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+        _ => PathBuf::from("."),
+    };
+
+    for keys in &unused_keys {
+        validator.unused_key(keys);
+    }
+    validation::validate(&config, &mut validator);
+    match validator.report() {
+        0 => {}
+        1 => {
+            return Err(RsjError::Config(format!(
+                "Failed to load `{}` due to 1 previous error.",
+                config_path.display()
+            )));
+        }
+        errors => {
+            return Err(RsjError::Config(format!(
+                "Failed to load `{}` due to {} previous errors.",
+                config_path.display(),
+                errors
+            )));
+        }
     }
 
     Ok(config)
 }
+
+// Walk up from the current directory like cargo does. The path stays relative to
+// the current directory so messages and diagnostics show short paths.
+fn find_manifest() -> Result<PathBuf, RsjError> {
+    let cwd =
+        env::current_dir().map_err(|e| RsjError::io("Failed to read the current directory", e))?;
+
+    let mut relative = PathBuf::new();
+    for dir in cwd.ancestors() {
+        if dir.join(MANIFEST_NAME).is_file() {
+            return Ok(relative.join(MANIFEST_NAME));
+        }
+        relative.push("..");
+    }
+
+    Err(RsjError::Config(format!(
+        "Error: Could not find `{}` in `{}` or any parent directory. Run 'rsj init' to create a new project.",
+        MANIFEST_NAME,
+        cwd.display()
+    )))
+}
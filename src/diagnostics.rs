@@ -0,0 +1,333 @@
+use colored::Colorize;
+use regex::Regex;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+static HEADER: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(.+\.java):(\d+): (error|warning): (.*)$").unwrap());
+// Maven reports `[ERROR] /path/Main.java:[3,9] message` without a source echo
+static MAVEN_HEADER: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\[(ERROR|WARNING)\] (.+\.java):\[(\d+),(\d+)\] (.*)$").unwrap());
+// javac's own "N errors" tally, the caller prints a summary instead
+static COUNT_LINE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\d+ (errors?|warnings?)$").unwrap());
+
+/// A single error or warning about a line of a file, from javac or rsj itself.
+pub struct Diagnostic {
+    pub path: PathBuf,
+    pub line: usize,
+    pub column: Option<usize>,
+    pub width: usize, // Characters the caret underlines, starting at `column`
+    pub is_error: bool,
+    pub message: String,
+    pub notes: Vec<String>,
+}
+
+/// Translates paths inside the generated Gradle project back to the user's `src/`.
+pub struct SourceMap {
+    working_dir: PathBuf,
+    roots: Vec<(PathBuf, PathBuf)>,
+}
+
+impl SourceMap {
+    /// `working_dir` is where the compiler ran, relative diagnostic paths start there.
+    pub fn new(working_dir: &Path) -> Self {
+        SourceMap {
+            working_dir: working_dir.to_path_buf(),
+            roots: Vec::new(),
+        }
+    }
+
+    /// Map files below `generated_root` to the same relative path below `src_dir`. A
+    /// root added more than once maps each file to the directory that has it.
+    pub fn with_root(mut self, generated_root: &Path, src_dir: &Path) -> Self {
+        let generated_root = generated_root
+            .canonicalize()
+            .unwrap_or_else(|_| generated_root.to_path_buf());
+        self.roots.push((generated_root, src_dir.to_path_buf()));
+        self
+    }
+
+    // Map a diagnostic onto the original file. The package rewrite keeps every line
+    // where it was, so only the column of the rewritten line may need adjusting.
+    fn translate(&self, diagnostic: &mut Diagnostic) {
+        let generated = self.working_dir.join(&diagnostic.path);
+        let generated = generated.canonicalize().unwrap_or(generated);
+        let candidates: Vec<PathBuf> = self
+            .roots
+            .iter()
+            .filter_map(|(generated_root, src_dir)| {
+                generated
+                    .strip_prefix(generated_root)
+                    .ok()
+                    .map(|relative| src_dir.join(relative))
+            })
+            .collect();
+        // A root may be filled from several directories, the file exists in one of them
+        let Some(original) = candidates
+            .iter()
+            .find(|candidate| candidate.is_file())
+            .or(candidates.first())
+            .cloned()
+        else {
+            return;
+        };
+        let original = original
+            .strip_prefix(".")
+            .map(Path::to_path_buf)
+            .unwrap_or(original);
+
+        if let (Some(column), Some(generated_line), Some(original_line)) = (
+            diagnostic.column,
+            read_line(&generated, diagnostic.line),
+            read_line(&original, diagnostic.line),
+        ) {
+            diagnostic.column = Some(translate_column(&generated_line, &original_line, column));
+        }
+
+        diagnostic.path = original;
+    }
+}
+
+fn read_line(path: &Path, line: usize) -> Option<String> {
+    let content = fs::read_to_string(path).ok()?;
+    content
+        .lines()
+        .nth(line.checked_sub(1)?)
+        .map(|l| l.trim_start_matches('\u{feff}').to_string())
+}
+
+// Columns inside the text both lines end with keep their distance from the end,
+// anything before that (i.e. the package declaration itself) stays as reported.
+fn translate_column(generated: &str, original: &str, column: usize) -> usize {
+    let generated: Vec<char> = generated.chars().collect();
+    let original: Vec<char> = original.chars().collect();
+    let common_suffix = generated
+        .iter()
+        .rev()
+        .zip(original.iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let suffix_start = generated.len() - common_suffix + 1;
+    if column >= suffix_start {
+        column + original.len() - generated.len()
+    } else {
+        column.min(original.len().max(1))
+    }
+}
+
+/// Parse the diagnostics in compiler output, skipping every other line.
+fn parse_javac_output(output: &str) -> Vec<Diagnostic> {
+    let mut diagnostics: Vec<Diagnostic> = Vec::new();
+    let mut lines = output.lines().peekable();
+
+    while let Some(line) = lines.next() {
+        if let Some(caps) = MAVEN_HEADER.captures(line) {
+            let diagnostic = Diagnostic {
+                path: PathBuf::from(&caps[2]),
+                line: caps[3].parse().unwrap_or(1),
+                column: caps[4].parse().ok(),
+                width: 1,
+                is_error: &caps[1] == "ERROR",
+                message: caps[5].to_string(),
+                notes: Vec::new(),
+            };
+            diagnostics.push(diagnostic);
+            continue;
+        }
+
+        let Some(caps) = HEADER.captures(line) else {
+            continue;
+        };
+
+        let mut diagnostic = Diagnostic {
+            path: PathBuf::from(&caps[1]),
+            line: caps[2].parse().unwrap_or(1),
+            column: None,
+            width: 1,
+            is_error: &caps[3] == "error",
+            message: caps[4].to_string(),
+            notes: Vec::new(),
+        };
+
+        // javac echoes the offending line, followed by a caret under the column
+        if let Some(next) = lines.peek()
+            && !HEADER.is_match(next)
+        {
+            lines.next();
+            if let Some(caret) = lines.peek()
+                && caret.trim() == "^"
+            {
+                diagnostic.column = Some(caret.chars().position(|c| c == '^').unwrap() + 1);
+                lines.next();
+            }
+        }
+
+        // Indented follow-up lines such as `symbol:` and `location:`
+        while let Some(next) = lines.peek() {
+            if !next.starts_with(' ') || next.trim().is_empty() {
+                break;
+            }
+            diagnostic.notes.push(next.trim().to_string());
+            lines.next();
+        }
+
+        diagnostics.push(diagnostic);
+    }
+
+    diagnostics
+}
+
+/// Renders compiler output line by line as a build tool prints it, translating the
+/// diagnostics to the user's sources in a rustc-like format as soon as each is complete.
+pub struct DiagnosticRenderer<'a> {
+    source_map: &'a SourceMap,
+    pending: Vec<String>, // Lines of the javac diagnostic being read
+    seen: Vec<(PathBuf, usize, Option<usize>, String)>,
+    errors: usize,
+}
+
+impl<'a> DiagnosticRenderer<'a> {
+    pub fn new(source_map: &'a SourceMap) -> Self {
+        DiagnosticRenderer {
+            source_map,
+            pending: Vec::new(),
+            seen: Vec::new(),
+            errors: 0,
+        }
+    }
+
+    /// Take one line of output, returning what can be shown so far.
+    pub fn line(&mut self, line: &str) -> String {
+        if !self.pending.is_empty() && self.continues_diagnostic(line) {
+            self.pending.push(line.to_string());
+            return String::new();
+        }
+
+        let mut rendered = self.flush();
+        if HEADER.is_match(line) {
+            self.pending.push(line.to_string());
+        } else if MAVEN_HEADER.is_match(line) {
+            rendered.push_str(&self.render(line));
+        } else if !COUNT_LINE.is_match(line) {
+            rendered.push_str(line);
+            rendered.push('\n');
+        }
+        rendered
+    }
+
+    /// Render what is left once the output ends, and return the number of errors.
+    pub fn finish(mut self) -> (String, usize) {
+        let rendered = self.flush();
+        (rendered, self.errors)
+    }
+
+    // javac follows the header with the offending line, a caret under the column and
+    // indented notes such as `symbol:` and `location:`
+    fn continues_diagnostic(&self, line: &str) -> bool {
+        if HEADER.is_match(line) || MAVEN_HEADER.is_match(line) {
+            return false;
+        }
+        match self.pending.len() {
+            1 => true,
+            2 if line.trim() == "^" => true,
+            _ => line.starts_with(' ') && !line.trim().is_empty(),
+        }
+    }
+
+    fn flush(&mut self) -> String {
+        if self.pending.is_empty() {
+            return String::new();
+        }
+        let chunk = self.pending.join("\n");
+        self.pending.clear();
+        self.render(&chunk)
+    }
+
+    fn render(&mut self, chunk: &str) -> String {
+        let diagnostics = parse_javac_output(chunk);
+        let mut rendered = String::new();
+        for mut diagnostic in diagnostics {
+            // Maven prints every compiler error twice, once in the log and once in the summary
+            let key = (
+                diagnostic.path.clone(),
+                diagnostic.line,
+                diagnostic.column,
+                diagnostic.message.clone(),
+            );
+            if self.seen.contains(&key) {
+                continue;
+            }
+            self.seen.push(key);
+
+            if diagnostic.is_error {
+                self.errors += 1;
+            }
+            self.source_map.translate(&mut diagnostic);
+            rendered.push_str(&render_diagnostic(&diagnostic));
+            rendered.push('\n');
+        }
+        rendered
+    }
+}
+
+/// Render one diagnostic like rustc does, with the source line and a caret.
+pub fn render_diagnostic(diagnostic: &Diagnostic) -> String {
+    let level = if diagnostic.is_error {
+        "error".red().bold()
+    } else {
+        "warning".yellow().bold()
+    };
+    let source_line = read_line(&diagnostic.path, diagnostic.line);
+    let number = diagnostic.line.to_string();
+    let gutter = " ".repeat(number.len());
+    let bar = "|".blue().bold();
+
+    let mut out = format!("{}: {}\n", level, diagnostic.message.bold());
+    // Line 0 stands for the file as a whole
+    let location = match (diagnostic.line, diagnostic.column) {
+        (0, _) => String::new(),
+        (line, Some(column)) => format!(":{}:{}", line, column),
+        (line, None) => format!(":{}", line),
+    };
+    out.push_str(&format!(
+        "{}{} {}{}\n",
+        gutter,
+        "-->".blue().bold(),
+        diagnostic.path.display(),
+        location
+    ));
+
+    if let Some(source_line) = source_line {
+        out.push_str(&format!("{} {}\n", gutter, bar));
+        out.push_str(&format!(
+            "{} {} {}\n",
+            number.blue().bold(),
+            bar,
+            source_line
+        ));
+        if let Some(column) = diagnostic.column {
+            // Reproduce tabs so the caret lines up with the snippet
+            let padding: String = source_line
+                .chars()
+                .take(column.saturating_sub(1))
+                .map(|c| if c == '\t' { '\t' } else { ' ' })
+                .collect();
+            let caret = "^".repeat(diagnostic.width.max(1));
+            let caret = if diagnostic.is_error {
+                caret.red().bold()
+            } else {
+                caret.yellow().bold()
+            };
+            out.push_str(&format!("{} {} {}{}\n", gutter, bar, padding, caret));
+        }
+    }
+
+    for note in &diagnostic.notes {
+        out.push_str(&format!("{} {} {}\n", gutter, "=".blue().bold(), note));
+    }
+
+    out
+}
@@ -0,0 +1,113 @@
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+
+/// Every way an rsj command can fail. Each variant maps to its own exit code so
+/// scripts can tell a broken `rsj.toml` from a failed compilation.
+#[derive(Debug)]
+pub enum RsjError {
+    /// `rsj.toml` is missing, unreadable or invalid.
+    Config(String),
+    /// A filesystem operation failed.
+    Io { context: String, source: io::Error },
+    /// A source file could not be processed (e.g. a mismatched package).
+    Source(String),
+    /// The project layout does not allow the command (e.g. `init` over a project).
+    Project(String),
+    /// `rsj.lock` is missing or does not match the resolved dependencies.
+    Lock(String),
+    /// An external program such as `gradle` or `java` could not be started.
+    ToolNotFound { tool: String, source: io::Error },
+    /// Gradle ran but failed. `status` is its exit code, if it had one.
+    BuildFailed { status: Option<i32>, errors: usize },
+    /// Tests ran and `failed` of them did not pass.
+    TestsFailed { failed: usize },
+    /// The build succeeded but the expected JAR is not there.
+    MissingJar(PathBuf),
+    /// The Java application itself exited unsuccessfully.
+    JavaFailed { status: Option<i32> },
+}
+
+impl RsjError {
+    pub fn io(context: impl Into<String>, source: io::Error) -> Self {
+        RsjError::Io {
+            context: context.into(),
+            source,
+        }
+    }
+
+    pub fn tool_not_found(tool: impl Into<String>, source: io::Error) -> Self {
+        RsjError::ToolNotFound {
+            tool: tool.into(),
+            source,
+        }
+    }
+
+    /// The process exit code for this error. A failing Java application passes its
+    /// own exit code through, like `cargo run` does.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            RsjError::Config(_) => 2,
+            RsjError::Io { .. } => 3,
+            RsjError::Source(_) => 4,
+            RsjError::Project(_) => 5,
+            RsjError::Lock(_) => 6,
+            RsjError::TestsFailed { .. } => 100,
+            RsjError::BuildFailed { .. } => 101,
+            RsjError::MissingJar(_) => 102,
+            RsjError::ToolNotFound { .. } => 127,
+            RsjError::JavaFailed { status } => match status {
+                Some(0) | None => 1,
+                Some(code) => *code,
+            },
+        }
+    }
+}
+
+impl fmt::Display for RsjError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RsjError::Config(msg)
+            | RsjError::Source(msg)
+            | RsjError::Project(msg)
+            | RsjError::Lock(msg) => {
+                write!(f, "{}", msg)
+            }
+            RsjError::Io { context, source } => write!(f, "{}: {}", context, source),
+            RsjError::ToolNotFound { tool, source } => {
+                write!(f, "Failed to run `{}`: {}", tool, source)
+            }
+            RsjError::BuildFailed { status, errors } => {
+                match errors {
+                    0 => write!(f, "Build failed")?,
+                    1 => write!(f, "Build failed due to 1 compilation error")?,
+                    n => write!(f, "Build failed due to {} compilation errors", n)?,
+                }
+                match status {
+                    Some(code) => write!(f, " (exit status: {}).", code),
+                    None => write!(f, " (terminated by signal)."),
+                }
+            }
+            RsjError::TestsFailed { failed } => match failed {
+                1 => write!(f, "1 test failed."),
+                n => write!(f, "{} tests failed.", n),
+            },
+            RsjError::MissingJar(path) => {
+                write!(f, "Build output JAR not found at `{}`.", path.display())
+            }
+            RsjError::JavaFailed { status } => match status {
+                Some(code) => write!(f, "Java application exited with status {}.", code),
+                None => write!(f, "Java application was terminated by a signal."),
+            },
+        }
+    }
+}
+
+impl std::error::Error for RsjError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            RsjError::Io { source, .. } | RsjError::ToolNotFound { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,89 @@
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::Path;
+use walkdir::WalkDir;
+
+use crate::error::RsjError;
+
+// Directories inside the Gradle or Maven project that hold outputs rather than inputs
+const IGNORED_DIRS: [&str; 3] = ["build", ".gradle", "target"];
+
+/// Hex encoded SHA-256 of `bytes`.
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+/// Fingerprint every input file of the generated Gradle or Maven project together
+/// with the task being run, so an unchanged project can skip invoking the build tool.
+pub fn fingerprint_project(project_dir: &Path, task: &str) -> Result<String, RsjError> {
+    fingerprint(project_dir, task, &IGNORED_DIRS)
+}
+
+/// Fingerprint every file below `dir` together with `inputs`, e.g. the sources the
+/// javac backend compiles, where `build` or `target` may well be a package.
+pub fn fingerprint_dir(dir: &Path, inputs: &str) -> Result<String, RsjError> {
+    fingerprint(dir, inputs, &[])
+}
+
+fn fingerprint(dir: &Path, inputs: &str, ignored: &[&str]) -> Result<String, RsjError> {
+    let mut hasher = Sha256::new();
+    hasher.update(env!("CARGO_PKG_VERSION"));
+    hasher.update([0]);
+    hasher.update(inputs);
+
+    let entries = WalkDir::new(dir)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|e| {
+            e.depth() != 1 || !ignored.contains(&e.file_name().to_str().unwrap_or(""))
+        });
+
+    for entry in entries {
+        let entry = entry.map_err(|e| {
+            RsjError::io(
+                format!("Failed to walk `{}`", dir.display()),
+                e.into_io_error()
+                    .unwrap_or_else(|| std::io::Error::other("filesystem loop")),
+            )
+        })?;
+        if !entry.file_type().is_file() {
+            continue;
+        }
+
+        let relative = entry.path().strip_prefix(dir).unwrap();
+        let content = fs::read(entry.path())
+            .map_err(|e| RsjError::io(format!("Failed to read `{}`", entry.path().display()), e))?;
+        hasher.update([0]);
+        hasher.update(relative.to_string_lossy().as_bytes());
+        hasher.update([0]);
+        hasher.update(sha256_hex(&content));
+    }
+
+    Ok(sha256_hex(&hasher.finalize()))
+}
+
+/// Whether the last successful run of `name` had the same fingerprint.
+pub fn is_fresh(fingerprint_dir: &Path, name: &str, fingerprint: &str) -> bool {
+    fs::read_to_string(fingerprint_dir.join(name)).is_ok_and(|stored| stored.trim() == fingerprint)
+}
+
+/// Remember `fingerprint` as the state of the last successful run of `name`.
+pub fn record(fingerprint_dir: &Path, name: &str, fingerprint: &str) -> Result<(), RsjError> {
+    fs::create_dir_all(fingerprint_dir)
+        .map_err(|e| RsjError::io("Failed to create fingerprint directory", e))?;
+    fs::write(fingerprint_dir.join(name), fingerprint)
+        .map_err(|e| RsjError::io("Failed to write fingerprint", e))
+}
+
+/// Forget the fingerprint of `name`, e.g. after a failed build.
+pub fn clear(fingerprint_dir: &Path, name: &str) {
+    let _ = fs::remove_file(fingerprint_dir.join(name));
+}
+
+/// Forget every recorded fingerprint, forcing the next build to run.
+pub fn clear_all(fingerprint_dir: &Path) {
+    let _ = fs::remove_dir_all(fingerprint_dir);
+}
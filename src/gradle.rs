@@ -1,124 +1,299 @@
+use std::env;
 use std::fs;
-use std::path::Path;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::OnceLock;
+use walkdir::WalkDir;
+
+use crate::backend::{
+    BuildBackend, DiagnosticStream, copy_standard_layout, run_build_tool, standard_source_map,
+};
+use crate::config::{Config, DependencyKind, Exclusion, Scope};
+use crate::error::RsjError;
+use crate::fingerprint;
+use crate::jdk::{self, Jdk};
+use crate::lock::{Pin, ResolvedArtifact};
+use crate::utils::{
+    FINGERPRINT_PATH, GRADLE_PATH, find_program, home_dir, printinfo, write_if_changed,
+};
+
+// File below `build/` the `rsjResolve` task lists the runtime classpath in
+const RESOLVED_PATH: &str = "rsj-resolved.txt";
+
+// The current Gradle 8 release, used without `gradle_version` or a suitable installed Gradle
+const DEFAULT_GRADLE_VERSION: &str = "8.14.3";
+
+/// Builds through a generated Gradle project in `rsj_build/gradle`.
+pub struct GradleBackend;
+
+impl BuildBackend for GradleBackend {
+    fn name(&self) -> &'static str {
+        "gradle"
+    }
+
+    fn prepare(
+        &self,
+        config: &Config,
+        src_dir: &Path,
+        tests_dir: &Path,
+        temp_path: &Path,
+        pins: &[Pin],
+    ) -> Result<(), RsjError> {
+        setup_gradle_project(config, src_dir, tests_dir, temp_path, pins)
+    }
 
-use crate::config::Config;
-use crate::utils::copy_src_files;
+    fn build(&self, config: &Config, temp_path: &Path) -> Result<(), RsjError> {
+        let use_shadow = config.use_shadow();
+        let task = if use_shadow { "shadowJar" } else { "build" };
+
+        // Skip Gradle entirely if nothing changed since the last successful build
+        let gradle_dir = temp_path.join(GRADLE_PATH);
+        let fingerprint_dir = temp_path.join(FINGERPRINT_PATH);
+        let current = fingerprint::fingerprint_project(&gradle_dir, task)?;
+        if fingerprint::is_fresh(&fingerprint_dir, task, &current)
+            && self.jar_path(config, temp_path).exists()
+        {
+            printinfo("Build is up to date.");
+            return Ok(());
+        }
+
+        // Run Gradle build
+        fingerprint::clear(&fingerprint_dir, task);
+        run_gradle(config, temp_path, &[task, "rsjResolve"])?;
+        fingerprint::record(&fingerprint_dir, task, &current)
+    }
 
-pub const GRADLE_PATH: &str = "gradle";
+    fn jar_path(&self, config: &Config, temp_path: &Path) -> PathBuf {
+        temp_path
+            .join(GRADLE_PATH)
+            .join("build")
+            .join("libs")
+            .join(format!(
+                "{}-{}.jar",
+                config.project.name, config.project.version
+            ))
+    }
+
+    fn resolved_artifacts(
+        &self,
+        _config: &Config,
+        temp_path: &Path,
+    ) -> Result<Vec<ResolvedArtifact>, RsjError> {
+        // Written by the `rsjResolve` task as `group:name:version:classifier<TAB>path`
+        let path = temp_path
+            .join(GRADLE_PATH)
+            .join("build")
+            .join(RESOLVED_PATH);
+        let content = fs::read_to_string(&path)
+            .map_err(|e| RsjError::io("Gradle did not report the resolved dependencies", e))?;
+
+        let mut artifacts = Vec::new();
+        for line in content.lines().filter(|line| !line.trim().is_empty()) {
+            let Some((coordinates, file)) = line.split_once('\t') else {
+                continue;
+            };
+            let parts: Vec<&str> = coordinates.split(':').collect();
+            let [group, name, version, classifier] = parts.as_slice() else {
+                continue;
+            };
+            artifacts.push(ResolvedArtifact {
+                group: group.to_string(),
+                name: name.to_string(),
+                version: version.to_string(),
+                classifier: Some(classifier.to_string()).filter(|c| !c.is_empty()),
+                path: PathBuf::from(file),
+            });
+        }
+        Ok(artifacts)
+    }
 
-// Helper to write content to file with error mapping
-fn write_file(path: &Path, content: &str) -> Result<(), String> {
-    fs::write(path, content).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+    fn test_results_dir(&self, temp_path: &Path) -> Option<PathBuf> {
+        Some(
+            temp_path
+                .join(GRADLE_PATH)
+                .join("build")
+                .join("test-results")
+                .join("test"),
+        )
+    }
+
+    fn run_tests(
+        &self,
+        config: &Config,
+        temp_path: &Path,
+        filter: Option<&str>,
+        nocapture: bool,
+    ) -> Result<(), RsjError> {
+        // Cargo-style substring filter on the fully qualified test name
+        let pattern = filter.map(|f| format!("*{}*", f));
+        let mut args = vec!["test"];
+        if let Some(pattern) = &pattern {
+            args.extend(["--tests", pattern.as_str()]);
+        }
+        if nocapture {
+            args.push("-Pnocapture");
+        }
+
+        run_gradle(config, temp_path, &args)
+    }
+}
+
+// Run Gradle with the given arguments in the generated project
+fn run_gradle(config: &Config, temp_path: &Path, args: &[&str]) -> Result<(), RsjError> {
+    // Define the Gradle project directory
+    let gradle_project_dir = temp_path.join(GRADLE_PATH);
+
+    // Prefer the generated wrapper, which runs exactly the configured Gradle version
+    let gradlew_path = gradle_project_dir.join("gradlew");
+    let program = if gradlew_path.exists() && wrapper_jar_path(&gradle_project_dir).exists() {
+        "./gradlew".to_string()
+    } else {
+        match find_gradle(config) {
+            Some(gradle) => gradle.display().to_string(),
+            None => {
+                return Err(RsjError::tool_not_found(
+                    "gradle",
+                    io::Error::new(
+                        io::ErrorKind::NotFound,
+                        "Gradle is not installed and no distribution was found in the Gradle wrapper cache",
+                    ),
+                ));
+            }
+        }
+    };
+
+    // Gradle reports compiler diagnostics on stderr
+    run_build_tool(
+        &program,
+        args,
+        &gradle_project_dir,
+        &standard_source_map(config, &gradle_project_dir),
+        DiagnosticStream::Stderr,
+    )
 }
 
-pub fn setup_gradle_project(
+// Helper to write content to file, leaving unchanged files untouched
+fn write_file(path: &Path, content: &str) -> Result<(), RsjError> {
+    write_if_changed(path, content)
+}
+
+fn setup_gradle_project(
     config: &Config,
-    src_dir: &str,
+    src_dir: &Path,
+    tests_dir: &Path,
     temp_path: &Path,
-) -> Result<(), String> {
+    pins: &[Pin],
+) -> Result<(), RsjError> {
     // Get versions from config or use defaults
-    let gradle_ver = config.project.gradle_version.as_deref().unwrap_or("8.4");
     let shadow_ver = config
         .project
         .shadow_plugin_version
         .as_deref()
         .unwrap_or("7.1.2");
-    let use_shadow = config.project.use_shadow.unwrap_or(true);
+    let use_shadow = config.use_shadow();
 
     // Setup directories
     let gradle_dir = temp_path.join(GRADLE_PATH);
     setup_gradle_directories(&gradle_dir)?;
 
     // Copy source files
-    setup_source_files(config, src_dir, &gradle_dir)?;
+    copy_standard_layout(config, src_dir, tests_dir, &gradle_dir)?;
 
     // Write Gradle configuration files
-    write_gradle_config_files(config, &gradle_dir, shadow_ver, use_shadow)?;
+    write_gradle_config_files(config, &gradle_dir, shadow_ver, use_shadow, pins)?;
 
     // Setup Gradle wrapper
-    create_gradle_wrapper(&gradle_dir, gradle_ver)?;
+    create_gradle_wrapper(config, &gradle_dir)?;
 
     Ok(())
 }
 
 // Setup Gradle directory structure
-fn setup_gradle_directories(gradle_dir: &Path) -> Result<(), String> {
+fn setup_gradle_directories(gradle_dir: &Path) -> Result<(), RsjError> {
     // Create main directories
     fs::create_dir_all(gradle_dir.join("src/main/java"))
-        .map_err(|e| format!("Failed to create Gradle project structure: {}", e))?;
+        .map_err(|e| RsjError::io("Failed to create Gradle project structure", e))?;
+
+    // Create test directories
+    fs::create_dir_all(gradle_dir.join("src/test/java"))
+        .map_err(|e| RsjError::io("Failed to create test directory", e))?;
 
     // Create resources directory
     fs::create_dir_all(gradle_dir.join("src/main/resources"))
-        .map_err(|e| format!("Failed to create resources directory: {}", e))?;
+        .map_err(|e| RsjError::io("Failed to create resources directory", e))?;
 
     Ok(())
 }
 
-// Copy source files to Gradle structure
-fn setup_source_files(config: &Config, src_dir: &str, gradle_dir: &Path) -> Result<(), String> {
-    // Prepare the path for Java files
-    let java_path = format!(
-        "src/main/java/{}",
-        &config.project.base_namespace.replace(".", "/")
-    );
-
-    // Copy source files with correct namespace
-    copy_src_files(
-        src_dir,
-        &gradle_dir.join(java_path),
-        &config.project.base_namespace,
-    )
-}
-
 // Write all Gradle configuration files
 fn write_gradle_config_files(
     config: &Config,
     gradle_dir: &Path,
     shadow_ver: &str,
     use_shadow: bool,
-) -> Result<(), String> {
+    pins: &[Pin],
+) -> Result<(), RsjError> {
+    // The JDK the javac backend and `rsj run` use. Without one Gradle searches
+    // itself, and reports the missing version.
+    let jdk = jdk::project_jdk(config).ok().flatten();
+
     // Write settings.gradle
     write_settings_gradle(config, gradle_dir)?;
 
     // Write gradle.properties
-    write_gradle_properties(gradle_dir)?;
+    write_gradle_properties(gradle_dir, jdk.as_ref())?;
 
     // Write build.gradle
-    write_build_gradle(config, gradle_dir, shadow_ver, use_shadow)?;
+    write_build_gradle(
+        config,
+        gradle_dir,
+        shadow_ver,
+        use_shadow,
+        pins,
+        jdk.as_ref(),
+    )?;
 
     Ok(())
 }
 
 // Write settings.gradle file
-fn write_settings_gradle(config: &Config, gradle_dir: &Path) -> Result<(), String> {
-    let settings = format!(
-        r#"rootProject.name = '{}'
-
-dependencyResolutionManagement {{
-    repositories {{
-        mavenCentral()
-        google()
-        gradlePluginPortal()
-    }}
-}}"#,
-        config.project.name
-    );
-
-    write_file(&gradle_dir.join("settings.gradle"), &settings)
+fn write_settings_gradle(config: &Config, gradle_dir: &Path) -> Result<(), RsjError> {
+    let mut settings = GroovyWriter::new();
+    settings.assign("rootProject.name", &config.project.name);
+    settings.blank();
+    settings.block("dependencyResolutionManagement", |w| {
+        w.block("repositories", |w| {
+            w.line("mavenCentral()");
+            w.line("google()");
+            w.line("gradlePluginPortal()");
+        });
+    });
+
+    write_file(&gradle_dir.join("settings.gradle"), &settings.finish())
 }
 
 // Write gradle.properties file
-fn write_gradle_properties(gradle_dir: &Path) -> Result<(), String> {
-    let properties = r#"# Gradle performance improvements
+fn write_gradle_properties(gradle_dir: &Path, jdk: Option<&Jdk>) -> Result<(), RsjError> {
+    let mut properties = String::from(
+        r#"# Gradle performance improvements
 org.gradle.jvmargs=-Xmx2g -XX:MaxMetaspaceSize=512m -XX:+HeapDumpOnOutOfMemoryError
 org.gradle.parallel=true
 org.gradle.caching=true
 org.gradle.configureondemand=true
 
 # Enable file system watching for faster incremental builds
-org.gradle.vfs.watch=true"#;
+org.gradle.vfs.watch=true"#,
+    );
+    // Make sure the toolchain finds the JDK rsj selected, wherever it is installed
+    if let Some(jdk) = jdk {
+        properties.push_str(&format!(
+            "\n\n# JDK selected for `java_version`\norg.gradle.java.installations.paths={}",
+            jdk.home.display().to_string().replace('\\', "/")
+        ));
+    }
 
-    write_file(&gradle_dir.join("gradle.properties"), properties)
+    write_file(&gradle_dir.join("gradle.properties"), &properties)
 }
 
 // Write build.gradle file
@@ -127,109 +302,526 @@ fn write_build_gradle(
     gradle_dir: &Path,
     shadow_ver: &str,
     use_shadow: bool,
-) -> Result<(), String> {
-    // Generate dependencies section
-    let deps = generate_gradle_dependencies(&config.dependencies);
-
-    // Build plugins section
-    let plugins_block = generate_plugins_block(shadow_ver, use_shadow);
-
-    // Build the main Gradle file content
-    let mut build = format!(
-        "plugins {{\n{}\n}}\n\ngroup = '{}'\nversion = '{}'\n\napplication {{\n    mainClass = '{}.{}'\n}}\n\njava {{\n    withSourcesJar()\n    withJavadocJar()\n}}\n\nrepositories {{\n    mavenCentral()\n    google()\n}}\n\ndependencies {{\n{}\n    testImplementation 'org.junit.jupiter:junit-jupiter-api:5.8.2'\n    testRuntimeOnly 'org.junit.jupiter:junit-jupiter-engine:5.8.2'\n}}\n\ntest {{\n    useJUnitPlatform()\n    testLogging {{\n        events \"passed\", \"skipped\", \"failed\"\n    }}\n}}\n\ntasks.named('jar') {{\n    manifest {{\n        attributes(\n            'Main-Class': '{}.{}'\n        )\n    }}\n}}",
-        plugins_block,
-        config.project.name,
-        config.project.version,
-        config.project.base_namespace,
-        config.project.main_class,
-        deps,
-        config.project.base_namespace,
-        config.project.main_class
-    );
+    pins: &[Pin],
+    jdk: Option<&Jdk>,
+) -> Result<(), RsjError> {
+    // Libraries have no entry point of their own
+    let main_class = config.main_class().filter(|_| !config.is_library());
+    let mut build = GroovyWriter::new();
+
+    build.block("plugins", |w| {
+        w.call("id", "java");
+        if main_class.is_some() {
+            w.call("id", "application");
+        }
+        w.call("id", "java-library");
+        if use_shadow {
+            w.command(&[
+                ("id", "com.github.johnrengelman.shadow"),
+                ("version", shadow_ver),
+            ]);
+        }
+    });
+    build.blank();
+    build.assign("group", &config.project.base_namespace);
+    build.assign("version", &config.project.version);
+    build.blank();
+    if let Some(main_class) = &main_class {
+        build.block("application", |w| w.assign("mainClass", main_class));
+        build.blank();
+    }
+    let java_version = config.project.java_version.as_deref();
+    // Compile with the same JDK as the javac backend, whatever runs Gradle: one of
+    // `java_version`, or else a newer one that compiles for it with `options.release`.
+    // Gradle does not download JDKs, as no toolchain resolver is configured.
+    let toolchain_version = jdk.map(|jdk| jdk.version.to_string());
+    let release = java_version.filter(|version| {
+        toolchain_version
+            .as_deref()
+            .is_some_and(|toolchain| toolchain != *version)
+    });
+    if java_version.is_some() || config.sources_jar() || config.javadoc_jar() {
+        build.block("java", |w| {
+            if let Some(java_version) = java_version {
+                w.block("toolchain", |w| {
+                    // Validated to be a number
+                    w.line(&format!(
+                        "languageVersion = JavaLanguageVersion.of({})",
+                        toolchain_version.as_deref().unwrap_or(java_version)
+                    ));
+                    if let Some(vendor) = &config.project.vendor {
+                        w.line(&format!(
+                            "vendor = JvmVendorSpec.matching({})",
+                            groovy_string(vendor)
+                        ));
+                    }
+                });
+            }
+            if config.sources_jar() {
+                w.line("withSourcesJar()");
+            }
+            if config.javadoc_jar() {
+                w.line("withJavadocJar()");
+            }
+        });
+        build.blank();
+    }
+    let javac_args = config.profile.javac_args();
+    if release.is_some() || !config.profile.debug || !javac_args.is_empty() {
+        build.block("tasks.withType(JavaCompile).configureEach", |w| {
+            if let Some(release) = release {
+                w.line(&format!("options.release = {}", release));
+            }
+            if !config.profile.debug {
+                w.line("options.debug = false");
+            }
+            if !javac_args.is_empty() {
+                let args: Vec<String> = javac_args.iter().map(|arg| groovy_string(arg)).collect();
+                w.line(&format!("options.compilerArgs += [{}]", args.join(", ")));
+            }
+        });
+        build.blank();
+    }
+    build.block("repositories", |w| {
+        w.line("mavenCentral()");
+        w.line("google()");
+    });
+    build.blank();
+
+    // Dependencies, pinned to the versions in rsj.lock
+    let dependencies = gradle_dependencies(config)?;
+    build.block("dependencies", |w| {
+        write_gradle_dependencies(w, &dependencies);
+        write_gradle_constraints(w, pins);
+        w.call(
+            "testImplementation",
+            "org.junit.jupiter:junit-jupiter-api:5.8.2",
+        );
+        w.call(
+            "testRuntimeOnly",
+            "org.junit.jupiter:junit-jupiter-engine:5.8.2",
+        );
+    });
+    build.blank();
+    build.block("test", |w| {
+        w.line("useJUnitPlatform()");
+        w.block("filter", |w| w.line("failOnNoMatchingTests = false"));
+        w.block("testLogging", |w| {
+            w.line("events 'passed', 'skipped', 'failed'");
+            w.line("showStandardStreams = project.hasProperty('nocapture')");
+        });
+    });
+    if let Some(main_class) = &main_class {
+        build.blank();
+        build.block("tasks.named('jar')", |w| {
+            w.block("manifest", |w| {
+                w.named_call("attributes", &[("'Main-Class'", main_class)]);
+            });
+        });
+    }
 
     // Add shadow configuration if enabled
     if use_shadow {
-        build.push_str(
-            "\nshadowJar {\n    archiveClassifier.set('')\n    archiveVersion.set(version)\n    mergeServiceFiles()\n}\n",
-        );
+        build.blank();
+        build.block("shadowJar", |w| {
+            w.line("archiveClassifier.set('')");
+            w.line("archiveVersion.set(version)");
+            w.line("mergeServiceFiles()");
+        });
+    }
+
+    // List the resolved runtime classpath for rsj.lock
+    build.blank();
+    build.block("tasks.register('rsjResolve')", |w| {
+        w.block("doLast", |w| {
+            w.block(
+                "def lines = configurations.runtimeClasspath.resolvedConfiguration.resolvedArtifacts.collect { artifact ->",
+                |w| {
+                    w.line("def id = artifact.moduleVersion.id");
+                    w.line(r#""${id.group}:${id.name}:${id.version}:${artifact.classifier ?: ''}\t${artifact.file.absolutePath}""#);
+                },
+            );
+            w.line(&format!(
+                "layout.buildDirectory.file({}).get().asFile.text = lines.sort().join('\\n')",
+                groovy_string(RESOLVED_PATH)
+            ));
+        });
+    });
+
+    write_file(&gradle_dir.join("build.gradle"), &build.finish())
+}
+
+/// One entry of the `dependencies` block.
+struct GradleDependency {
+    configuration: &'static str,
+    notation: String,
+    exclusions: Vec<Exclusion>,
+}
+
+// Map every dependency table to Gradle configurations, in declaration order
+fn gradle_dependencies(config: &Config) -> Result<Vec<GradleDependency>, RsjError> {
+    let mut dependencies = Vec::new();
+    for kind in [
+        DependencyKind::Normal,
+        DependencyKind::Dev,
+        DependencyKind::Build,
+    ] {
+        for (name, dependency) in config.dependency_table(kind) {
+            let coordinates = dependency.coordinates(name)?;
+            // `optional` only matters to consumers of a published POM, which Gradle
+            // does not generate for applications
+            let configuration = match (kind, dependency.scope()) {
+                (DependencyKind::Normal, scope) => scope.name(),
+                (DependencyKind::Dev, Scope::CompileOnly) => "testCompileOnly",
+                (DependencyKind::Dev, Scope::RuntimeOnly) => "testRuntimeOnly",
+                (DependencyKind::Dev, _) => "testImplementation",
+                (DependencyKind::Build, _) => "annotationProcessor",
+            };
+            dependencies.push(GradleDependency {
+                configuration,
+                notation: coordinates.notation(),
+                exclusions: dependency.exclusions(name)?,
+            });
+        }
     }
 
-    write_file(&gradle_dir.join("build.gradle"), &build)
+    Ok(dependencies)
+}
+
+fn write_gradle_dependencies(w: &mut GroovyWriter, dependencies: &[GradleDependency]) {
+    for dependency in dependencies {
+        if dependency.exclusions.is_empty() {
+            w.call(dependency.configuration, &dependency.notation);
+            continue;
+        }
+
+        w.call_block(dependency.configuration, &dependency.notation, |w| {
+            for exclusion in &dependency.exclusions {
+                match &exclusion.artifact {
+                    Some(artifact) => w.named_call(
+                        "exclude",
+                        &[("group", &exclusion.group), ("module", artifact)],
+                    ),
+                    None => w.named_call("exclude", &[("group", &exclusion.group)]),
+                }
+            }
+        });
+    }
 }
 
-// Generate the plugins block for build.gradle
-fn generate_plugins_block(shadow_ver: &str, use_shadow: bool) -> String {
-    let mut plugins = vec![
-        "    id 'java'".to_string(),
-        "    id 'application'".to_string(),
-        "    id 'java-library'".to_string(),
-    ];
+// Dependency constraints holding artifacts at their locked versions
+fn write_gradle_constraints(w: &mut GroovyWriter, pins: &[Pin]) {
+    if pins.is_empty() {
+        return;
+    }
 
-    if use_shadow {
-        plugins.push(format!(
-            "    id 'com.github.johnrengelman.shadow' version '{}'",
-            shadow_ver
-        ));
+    w.block("constraints", |w| {
+        for pin in pins {
+            let module = format!("{}:{}", pin.group, pin.name);
+            w.call_block("implementation", &module, |w| {
+                w.block("version", |w| {
+                    w.call(if pin.strict { "strictly" } else { "prefer" }, &pin.version)
+                });
+            });
+        }
+    });
+}
+
+/// Writes Groovy DSL line by line. Every value goes through `groovy_string`, so
+/// nothing from rsj.toml can end a string literal or start a `${}` interpolation.
+/// Method names, keys and `line` code are fixed by rsj and written as they are.
+struct GroovyWriter {
+    out: String,
+    depth: usize,
+}
+
+impl GroovyWriter {
+    fn new() -> Self {
+        GroovyWriter {
+            out: String::new(),
+            depth: 0,
+        }
+    }
+
+    /// A line of code that contains no values.
+    fn line(&mut self, code: &str) {
+        for _ in 0..self.depth {
+            self.out.push_str("    ");
+        }
+        self.out.push_str(code);
+        self.out.push('\n');
+    }
+
+    fn blank(&mut self) {
+        self.out.push('\n');
+    }
+
+    /// `method 'value'`
+    fn call(&mut self, method: &str, value: &str) {
+        self.command(&[(method, value)]);
+    }
+
+    /// A command chain such as `id 'plugin' version '1.0'`.
+    fn command(&mut self, calls: &[(&str, &str)]) {
+        let code: Vec<String> = calls
+            .iter()
+            .map(|(method, value)| format!("{} {}", method, groovy_string(value)))
+            .collect();
+        self.line(&code.join(" "));
+    }
+
+    /// `method key: 'value', ...`
+    fn named_call(&mut self, method: &str, arguments: &[(&str, &str)]) {
+        let arguments: Vec<String> = arguments
+            .iter()
+            .map(|(key, value)| format!("{}: {}", key, groovy_string(value)))
+            .collect();
+        self.line(&format!("{} {}", method, arguments.join(", ")));
     }
 
-    plugins.join("\n")
+    /// `name = 'value'`
+    fn assign(&mut self, name: &str, value: &str) {
+        self.line(&format!("{} = {}", name, groovy_string(value)));
+    }
+
+    /// `header { ... }`
+    fn block(&mut self, header: &str, body: impl FnOnce(&mut Self)) {
+        self.line(&format!("{} {{", header));
+        self.depth += 1;
+        body(self);
+        self.depth -= 1;
+        self.line("}");
+    }
+
+    /// `method('value') { ... }`
+    fn call_block(&mut self, method: &str, value: &str, body: impl FnOnce(&mut Self)) {
+        self.block(&format!("{}({})", method, groovy_string(value)), body);
+    }
+
+    fn finish(self) -> String {
+        self.out
+    }
+}
+
+// Quote a value as a single-quoted Groovy string, which never interpolates
+fn groovy_string(value: &str) -> String {
+    let mut quoted = String::from("'");
+    for c in value.chars() {
+        match c {
+            '\\' => quoted.push_str("\\\\"),
+            '\'' => quoted.push_str("\\'"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if c.is_control() => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('\'');
+    quoted
+}
+
+fn wrapper_jar_path(gradle_dir: &Path) -> PathBuf {
+    gradle_dir
+        .join("gradle")
+        .join("wrapper")
+        .join("gradle-wrapper.jar")
 }
 
-// Generate dependencies section for build.gradle
-fn generate_gradle_dependencies(
-    dependencies: &Option<std::collections::HashMap<String, String>>,
-) -> String {
-    dependencies
-        .as_ref()
-        .map(|deps| {
-            deps.iter()
-                .map(|(_, dep)| format!("    implementation '{}'", dep))
-                .collect::<Vec<String>>()
-                .join("\n")
+// `gradle_version`, or else the version of the installed Gradle, or else the default
+fn gradle_version(config: &Config) -> &str {
+    match &config.project.gradle_version {
+        Some(version) => version,
+        None => installed_gradle_version().unwrap_or(DEFAULT_GRADLE_VERSION),
+    }
+}
+
+// Version of the `gradle` on PATH, read from the name of its launcher JAR rather than
+// by starting it. Only Gradle 8.5 and later 8.x releases qualify: older ones do not
+// run on Java 21, and Gradle 9 drops APIs the shadow plugin needs.
+fn installed_gradle_version() -> Option<&'static str> {
+    static INSTALLED: OnceLock<Option<String>> = OnceLock::new();
+    INSTALLED
+        .get_or_init(|| {
+            let gradle = find_program(if cfg!(windows) {
+                "gradle.bat"
+            } else {
+                "gradle"
+            })?;
+            // `/usr/bin/gradle` and Homebrew's `bin/gradle` are links into the distribution
+            let home = gradle
+                .canonicalize()
+                .ok()?
+                .parent()?
+                .parent()?
+                .to_path_buf();
+            let version = [home.join("lib"), home.join("libexec/lib")]
+                .iter()
+                .filter_map(|lib| fs::read_dir(lib).ok())
+                .flatten()
+                .filter_map(Result::ok)
+                .find_map(|entry| {
+                    let name = entry.file_name().to_string_lossy().to_string();
+                    let version = name
+                        .strip_prefix("gradle-launcher-")?
+                        .strip_suffix(".jar")?;
+                    Some(version.to_string())
+                })?;
+            let mut parts = version.split('.').map(|part| part.parse::<u32>().ok());
+            match (parts.next()??, parts.next()??) {
+                (8, minor) if minor >= 5 => Some(version),
+                _ => None,
+            }
         })
-        .unwrap_or_default()
+        .as_deref()
+}
+
+// The configured distribution as a URL. Local zips become `file:` URLs, so the
+// wrapper installs them without touching the network.
+fn distribution_url(config: &Config) -> Result<String, RsjError> {
+    let Some(distribution) = &config.project.gradle_distribution_url else {
+        return Ok(format!(
+            "https://services.gradle.org/distributions/gradle-{}-bin.zip",
+            gradle_version(config)
+        ));
+    };
+    if distribution.contains("://") {
+        return Ok(distribution.clone());
+    }
+
+    let path = config.manifest_dir.join(distribution);
+    let path = path.canonicalize().map_err(|e| {
+        RsjError::io(
+            format!(
+                "Failed to find the Gradle distribution `{}`",
+                path.display()
+            ),
+            e,
+        )
+    })?;
+    let path = path.display().to_string().replace('\\', "/");
+    let path = path.trim_start_matches('/').replace(' ', "%20");
+    Ok(format!("file:///{}", path))
+}
+
+// The directory name the wrapper unpacks a distribution under, e.g. `gradle-8.4-bin`
+fn distribution_name(url: &str) -> &str {
+    let file = url.rsplit('/').next().unwrap_or(url);
+    file.strip_suffix(".zip").unwrap_or(file)
+}
+
+fn gradle_user_home() -> Option<PathBuf> {
+    env::var_os("GRADLE_USER_HOME")
+        .map(PathBuf::from)
+        .or_else(|| home_dir().map(|home| home.join(".gradle")))
+}
+
+// Find a Gradle to run without the wrapper: one on PATH, or else a distribution of the
+// configured version that an earlier wrapper run or the user left in the wrapper cache,
+// i.e. `~/.gradle/wrapper/dists/gradle-8.4-bin/<hash>/gradle-8.4/bin/gradle`
+fn find_gradle(config: &Config) -> Option<PathBuf> {
+    let executable = if cfg!(windows) {
+        "gradle.bat"
+    } else {
+        "gradle"
+    };
+    if let Some(gradle) = find_program(executable) {
+        return Some(gradle);
+    }
+
+    let url = distribution_url(config).ok()?;
+    let dists = gradle_user_home()?
+        .join("wrapper")
+        .join("dists")
+        .join(distribution_name(&url));
+    WalkDir::new(dists)
+        .min_depth(2)
+        .max_depth(2)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_map(Result::ok)
+        .map(|entry| entry.path().join("bin").join(executable))
+        .find(|path| path.is_file())
 }
 
-// Create Gradle wrapper files
-fn create_gradle_wrapper(gradle_dir: &Path, gradle_version: &str) -> Result<(), String> {
-    // Create wrapper directory
+// Create the Gradle wrapper: scripts and JAR from `gradle wrapper`, and properties
+// pointing at the configured distribution
+fn create_gradle_wrapper(config: &Config, gradle_dir: &Path) -> Result<(), RsjError> {
     let wrapper_dir = gradle_dir.join("gradle/wrapper");
     fs::create_dir_all(&wrapper_dir)
-        .map_err(|_| "Failed to create Gradle wrapper directory.".to_string())?;
+        .map_err(|e| RsjError::io("Failed to create Gradle wrapper directory", e))?;
+
+    // The JAR does not depend on the Gradle version, so it is only generated once
+    if !wrapper_jar_path(gradle_dir).exists() {
+        match find_gradle(config) {
+            Some(gradle) => {
+                if let Err(e) = generate_wrapper(config, &gradle, gradle_dir) {
+                    printinfo(&format!(
+                        "Could not generate the Gradle wrapper, running Gradle directly: {}",
+                        e
+                    ));
+                }
+            }
+            None => printinfo(
+                "No Gradle found to generate the wrapper with. Install Gradle or unpack a distribution into the Gradle wrapper cache.",
+            ),
+        }
+    }
 
-    // Write wrapper properties
-    let properties_content = format!(
-        "distributionBase=GRADLE_USER_HOME\ndistributionPath=wrapper/dists\ndistributionUrl=https://services.gradle.org/distributions/gradle-{}-bin.zip\nnetworkTimeout=10000\nzipStoreBase=GRADLE_USER_HOME\nzipStorePath=wrapper/dists",
-        gradle_version
+    let mut properties = format!(
+        "distributionBase=GRADLE_USER_HOME\ndistributionPath=wrapper/dists\ndistributionUrl={}\n",
+        distribution_url(config)?
     );
+    if let Some(checksum) = &config.project.gradle_distribution_sha256 {
+        properties.push_str(&format!("distributionSha256Sum={}\n", checksum));
+    }
+    properties.push_str(
+        "networkTimeout=10000\nzipStoreBase=GRADLE_USER_HOME\nzipStorePath=wrapper/dists\n",
+    );
+    write_file(&wrapper_dir.join("gradle-wrapper.properties"), &properties)
+}
+
+// Run `gradle wrapper` in an empty scratch project, so neither the generated build
+// script nor its plugins are involved, and copy the result over
+fn generate_wrapper(config: &Config, gradle: &Path, gradle_dir: &Path) -> Result<(), RsjError> {
+    let scratch =
+        tempfile::tempdir().map_err(|e| RsjError::io("Failed to create a scratch directory", e))?;
+    let project = scratch.path().join("wrapper");
+    fs::create_dir_all(&project)
+        .map_err(|e| RsjError::io("Failed to create a scratch directory", e))?;
     write_file(
-        &wrapper_dir.join("gradle-wrapper.properties"),
-        &properties_content,
+        &project.join("settings.gradle"),
+        "rootProject.name = 'wrapper'\n",
     )?;
 
-    // Create Unix wrapper script
-    let gradlew_content = r#"#!/bin/sh
-# Gradle wrapper script for Unix-based systems
-exec "$(dirname "$0")"/gradle/wrapper/gradle-wrapper.jar "$@""#;
-    write_file(&gradle_dir.join("gradlew"), gradlew_content)?;
-
-    // Make the script executable on Unix systems
-    #[cfg(not(target_os = "windows"))]
-    {
-        use std::os::unix::fs::PermissionsExt;
-        let metadata = fs::metadata(gradle_dir.join("gradlew"))
-            .map_err(|_| "Failed to get gradlew metadata.".to_string())?;
-        let mut permissions = metadata.permissions();
-        permissions.set_mode(0o755);
-        fs::set_permissions(gradle_dir.join("gradlew"), permissions)
-            .map_err(|_| "Failed to set executable permissions on gradlew.".to_string())?;
-    }
-
-    // Create Windows batch script
-    let gradlew_bat_content = r#"@rem Gradle wrapper script for Windows
-@echo off
-java -jar "%~dp0/gradle/wrapper/gradle-wrapper.jar" %*"#;
-    write_file(&gradle_dir.join("gradlew.bat"), gradlew_bat_content)?;
+    let output = Command::new(gradle)
+        .args([
+            "wrapper",
+            "--gradle-version",
+            gradle_version(config),
+            "--distribution-type",
+            "bin",
+        ])
+        .current_dir(&project)
+        .output()
+        .map_err(|e| RsjError::tool_not_found(gradle.display().to_string(), e))?;
+    if !output.status.success() {
+        return Err(RsjError::BuildFailed {
+            status: output.status.code(),
+            errors: 0,
+        });
+    }
 
+    let jar = PathBuf::from("gradle/wrapper/gradle-wrapper.jar");
+    for file in [Path::new("gradlew"), Path::new("gradlew.bat"), &jar] {
+        // `fs::copy` keeps the executable bit of `gradlew`
+        fs::copy(project.join(file), gradle_dir.join(file)).map_err(|e| {
+            RsjError::io(
+                format!("Failed to copy the generated `{}`", file.display()),
+                e,
+            )
+        })?;
+    }
+    printinfo("Generated the Gradle wrapper.");
     Ok(())
 }
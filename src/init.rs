@@ -0,0 +1,340 @@
+// Scaffolding for `rsj init` and `rsj new`. Options left out on the command line
+// are derived from the directory name and the git configuration.
+
+use std::fs;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use crate::commands::{InitOptions, Vcs};
+use crate::config::MANIFEST_NAME;
+use crate::error::RsjError;
+use crate::java;
+use crate::template::{Template, Variables};
+use crate::utils::{OUTPUT_PATH, printinfo};
+use crate::validation;
+
+// Mail providers whose domain says nothing about who owns the code
+const WEBMAIL_DOMAINS: &[&str] = &[
+    "gmail.com",
+    "googlemail.com",
+    "outlook.com",
+    "hotmail.com",
+    "live.com",
+    "yahoo.com",
+    "icloud.com",
+    "me.com",
+    "proton.me",
+    "protonmail.com",
+    "users.noreply.github.com",
+];
+
+const DEFAULT_NAMESPACE: &str = "com.example";
+
+const INITIAL_VERSION: &str = "0.1.0";
+
+// Helper to create a file with error mapping
+fn create_file(path: &Path, content: &str) -> Result<(), RsjError> {
+    fs::write(path, content)
+        .map_err(|e| RsjError::io(format!("Failed to write to `{}`", path.display()), e))
+}
+
+// Helper to create a directory with error mapping
+fn create_directory(path: &Path) -> Result<(), RsjError> {
+    fs::create_dir_all(path)
+        .map_err(|e| RsjError::io(format!("Failed to create `{}`", path.display()), e))
+}
+
+/// The values written to rsj.toml, after applying the defaults.
+struct Project {
+    name: String,
+    namespace: String,
+    main_class: Option<String>,
+    java_version: Option<String>,
+    lib: bool,
+}
+
+/// Create `dir` and scaffold a project in it, like `cargo new`.
+pub fn new_project(dir: &Path, options: &InitOptions) -> Result<(), RsjError> {
+    if dir.exists() {
+        return Err(RsjError::Project(format!(
+            "Error: Destination `{}` already exists. Use `rsj init` inside it instead.",
+            dir.display()
+        )));
+    }
+    create_directory(dir)?;
+    init_project(dir, options)
+}
+
+/// Scaffold a project in the existing directory `dir`.
+pub fn init_project(dir: &Path, options: &InitOptions) -> Result<(), RsjError> {
+    // Check if project files already exist
+    let config_path = dir.join(MANIFEST_NAME);
+    let src_dir = dir.join("src");
+
+    if config_path.exists() {
+        return Err(RsjError::Project(format!(
+            "Error: `{}` already exists.",
+            config_path.display()
+        )));
+    }
+
+    if src_dir.exists() {
+        return Err(RsjError::Project(format!(
+            "Error: `{}` directory already exists.",
+            src_dir.display()
+        )));
+    }
+
+    let template = Template::load(options.template.as_deref(), options.lib)?;
+    let project = resolve_options(dir, options, template.lib)?;
+    let variables = Variables {
+        name: project.name.clone(),
+        version: INITIAL_VERSION.to_string(),
+        namespace: project.namespace.clone(),
+        main_class: project.main_class.clone(),
+        java_version: project.java_version.clone(),
+    };
+
+    // Render everything first, so a broken template leaves nothing behind
+    let mut files = template.render(&variables)?;
+    if !template.has_manifest() {
+        files.insert(
+            0,
+            (
+                MANIFEST_NAME.to_string(),
+                manifest(&project, &template.manifest_sections(&variables)).into_bytes(),
+            ),
+        );
+    }
+    if let Some((path, _)) = files.iter().find(|(path, _)| dir.join(path).exists()) {
+        return Err(RsjError::Project(format!(
+            "Error: `{}` already exists.",
+            dir.join(path).display()
+        )));
+    }
+
+    create_directory(&src_dir)?;
+    for (path, content) in &files {
+        let path = dir.join(path);
+        if let Some(parent) = path.parent() {
+            create_directory(parent)?;
+        }
+        fs::write(&path, content)
+            .map_err(|e| RsjError::io(format!("Failed to write to `{}`", path.display()), e))?;
+    }
+
+    update_gitignore(dir)?;
+    if options.vcs == Vcs::Git {
+        init_git(dir)?;
+    }
+
+    let kind = if template.lib { "library" } else { "project" };
+    printinfo(&format!(
+        "Initialized a new RSJ {} `{}` with Gradle.",
+        kind, project.name
+    ));
+    Ok(())
+}
+
+// Fill in the options not given on the command line, and check them all before
+// anything is written
+fn resolve_options(dir: &Path, options: &InitOptions, lib: bool) -> Result<Project, RsjError> {
+    let name = match &options.name {
+        Some(name) => name.clone(),
+        None => directory_name(dir)?,
+    };
+    if let Err(message) = validation::check_project_name(&name) {
+        return Err(RsjError::Config(format!("Error: {}.", message)));
+    }
+
+    let namespace = match &options.namespace {
+        Some(namespace) => namespace.clone(),
+        None => git_config("user.email")
+            .and_then(|email| namespace_from_email(&email))
+            .unwrap_or_else(|| DEFAULT_NAMESPACE.to_string()),
+    };
+    if !java::is_qualified_name(&namespace) {
+        return Err(RsjError::Config(format!(
+            "Error: Invalid namespace `{}`, expected a Java package name such as `com.example`.",
+            namespace
+        )));
+    }
+
+    // Libraries only get a main class when one is asked for
+    let main_class = match &options.main_class {
+        Some(main_class) => Some(main_class.clone()),
+        None if lib => None,
+        None => Some("Main".to_string()),
+    };
+    if let Some(main_class) = &main_class
+        && !java::is_qualified_name(main_class)
+    {
+        return Err(RsjError::Config(format!(
+            "Error: Invalid main class `{}`, expected a Java class name such as `Main`.",
+            main_class
+        )));
+    }
+
+    if let Some(java_version) = &options.java_version
+        && let Err(message) = validation::check_java_version(java_version)
+    {
+        return Err(RsjError::Config(format!("Error: {}.", message)));
+    }
+
+    Ok(Project {
+        name,
+        namespace,
+        main_class,
+        java_version: options.java_version.clone(),
+        lib,
+    })
+}
+
+// The name of `dir` itself, which may be `.`
+fn directory_name(dir: &Path) -> Result<String, RsjError> {
+    let absolute = dir
+        .canonicalize()
+        .map_err(|e| RsjError::io(format!("Failed to read `{}`", dir.display()), e))?;
+    absolute
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .ok_or_else(|| {
+            RsjError::Config(format!(
+                "Error: Cannot derive a project name from `{}`, pass `--name`.",
+                absolute.display()
+            ))
+        })
+}
+
+// A value of `git config`, if git is installed and the key is set
+fn git_config(key: &str) -> Option<String> {
+    let output = Command::new("git")
+        .args(["config", "--get", key])
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    let value = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (output.status.success() && !value.is_empty()).then_some(value)
+}
+
+// `jane@tools.acme.io` becomes `io.acme.tools`
+fn namespace_from_email(email: &str) -> Option<String> {
+    let domain = email.rsplit_once('@')?.1.trim().to_lowercase();
+    if WEBMAIL_DOMAINS.contains(&domain.as_str()) {
+        return None;
+    }
+
+    let parts = domain
+        .split('.')
+        .rev()
+        .map(package_segment)
+        .collect::<Option<Vec<_>>>()?;
+    (parts.len() >= 2).then(|| parts.join("."))
+}
+
+// Turn a domain label into a package name segment, as the JLS suggests
+fn package_segment(label: &str) -> Option<String> {
+    let mut segment: String = label
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    if segment.is_empty() {
+        return None;
+    }
+    if segment.starts_with(|c: char| c.is_ascii_digit()) {
+        segment.insert(0, '_');
+    }
+    if !java::is_identifier(&segment) {
+        segment.push('_');
+    }
+    Some(segment)
+}
+
+// rsj.toml for templates without their own, ending with the template's `sections`
+fn manifest(project: &Project, sections: &str) -> String {
+    // Quote through the TOML serializer so any character survives
+    let quote = |value: &str| toml::Value::String(value.to_string()).to_string();
+
+    let mut manifest = format!(
+        "[project]\nname = {}\nversion = {}\n",
+        quote(&project.name),
+        quote(INITIAL_VERSION)
+    );
+    if let Some(main_class) = &project.main_class {
+        manifest.push_str(&format!("main_class = {}\n", quote(main_class)));
+    }
+    manifest.push_str(&format!("base_namespace = {}\n", quote(&project.namespace)));
+    if let Some(java_version) = &project.java_version {
+        manifest.push_str(&format!("java_version = {}\n", quote(java_version)));
+    }
+    if project.lib {
+        manifest.push_str(
+            "\n[lib]\n# sources = true # Build a sources JAR\n# javadoc = true # Build a Javadoc JAR\n",
+        );
+    }
+    if sections.is_empty() {
+        manifest
+            .push_str("\n# [dependencies]\n# junit = \"org.junit.jupiter:junit-jupiter:5.9.1\"\n");
+    } else {
+        manifest.push('\n');
+        manifest.push_str(sections);
+    }
+    manifest
+}
+
+// Keep the build output out of version control, preserving any existing entries
+fn update_gitignore(dir: &Path) -> Result<(), RsjError> {
+    let path = dir.join(".gitignore");
+    let entry = format!("/{}/", OUTPUT_PATH);
+
+    let mut content = match fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => {
+            return Err(RsjError::io(
+                format!("Failed to read `{}`", path.display()),
+                e,
+            ));
+        }
+    };
+    if content.lines().any(|line| line.trim() == entry) {
+        return Ok(());
+    }
+    if !content.is_empty() && !content.ends_with('\n') {
+        content.push('\n');
+    }
+    content.push_str(&entry);
+    content.push('\n');
+    create_file(&path, &content)
+}
+
+// Like cargo, leave an enclosing repository alone and only create a new one
+fn init_git(dir: &Path) -> Result<(), RsjError> {
+    let inside = Command::new("git")
+        .args(["rev-parse", "--is-inside-work-tree"])
+        .current_dir(dir)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status();
+    match inside {
+        Ok(status) if status.success() => return Ok(()),
+        Ok(_) => {}
+        Err(_) => {
+            printinfo("git is not installed, skipping `git init`.");
+            return Ok(());
+        }
+    }
+
+    let status = Command::new("git")
+        .args(["init", "--quiet"])
+        .current_dir(dir)
+        .status()
+        .map_err(|e| RsjError::tool_not_found("git", e))?;
+    if !status.success() {
+        return Err(RsjError::Project(format!(
+            "Error: `git init` failed in `{}`.",
+            dir.display()
+        )));
+    }
+    Ok(())
+}
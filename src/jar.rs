@@ -0,0 +1,201 @@
+use std::collections::{BTreeMap, HashSet};
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, DateTime, ZipArchive, ZipWriter};
+
+use crate::error::RsjError;
+
+const MANIFEST_PATH: &str = "META-INF/MANIFEST.MF";
+const SERVICES_PREFIX: &str = "META-INF/services/";
+
+/// Assembles a JAR from compiled classes, optionally merging dependency JARs into it
+/// the way ShadowJar does.
+pub struct JarBuilder {
+    attributes: Vec<(String, String)>,
+    dirs: Vec<PathBuf>,
+    merged_jars: Vec<PathBuf>,
+}
+
+impl JarBuilder {
+    pub fn new() -> Self {
+        JarBuilder {
+            attributes: vec![
+                ("Manifest-Version".to_string(), "1.0".to_string()),
+                (
+                    "Created-By".to_string(),
+                    format!("rsj {}", env!("CARGO_PKG_VERSION")),
+                ),
+            ],
+            dirs: Vec::new(),
+            merged_jars: Vec::new(),
+        }
+    }
+
+    /// Add a main manifest attribute such as `Main-Class`.
+    pub fn attribute(mut self, name: &str, value: &str) -> Self {
+        self.attributes.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// Add every file below `dir`, at its path relative to `dir`.
+    pub fn dir(mut self, dir: &Path) -> Self {
+        self.dirs.push(dir.to_path_buf());
+        self
+    }
+
+    /// Copy the entries of another JAR, merging service files and dropping signatures.
+    pub fn merge_jar(mut self, jar: &Path) -> Self {
+        self.merged_jars.push(jar.to_path_buf());
+        self
+    }
+
+    pub fn write(self, path: &Path) -> Result<(), RsjError> {
+        let jar_error = |e: zip::result::ZipError| {
+            RsjError::io(
+                format!("Failed to write `{}`", path.display()),
+                std::io::Error::other(e),
+            )
+        };
+        let io_error =
+            |e: std::io::Error| RsjError::io(format!("Failed to write `{}`", path.display()), e);
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(io_error)?;
+        }
+        let mut zip = ZipWriter::new(File::create(path).map_err(io_error)?);
+
+        // A fixed timestamp keeps the JAR byte-for-byte reproducible
+        let options = SimpleFileOptions::default()
+            .compression_method(CompressionMethod::Deflated)
+            .last_modified_time(DateTime::default());
+
+        // The manifest has to come first for `java -jar` and JarInputStream
+        zip.add_directory("META-INF/", options).map_err(jar_error)?;
+        zip.start_file(MANIFEST_PATH, options).map_err(jar_error)?;
+        zip.write_all(self.manifest().as_bytes())
+            .map_err(io_error)?;
+
+        let mut seen: HashSet<String> = HashSet::from(["META-INF/".to_string()]);
+        let mut services: BTreeMap<String, Vec<u8>> = BTreeMap::new();
+
+        for dir in &self.dirs {
+            let entries = WalkDir::new(dir)
+                .min_depth(1)
+                .sort_by_file_name()
+                .into_iter()
+                .filter_map(Result::ok);
+            for entry in entries {
+                let name = entry
+                    .path()
+                    .strip_prefix(dir)
+                    .unwrap()
+                    .to_string_lossy()
+                    .replace(std::path::MAIN_SEPARATOR, "/");
+
+                if entry.file_type().is_dir() {
+                    let name = format!("{}/", name);
+                    if seen.insert(name.clone()) {
+                        zip.add_directory(name, options).map_err(jar_error)?;
+                    }
+                    continue;
+                }
+
+                let content = fs::read(entry.path()).map_err(io_error)?;
+                if name.starts_with(SERVICES_PREFIX) {
+                    append_service(&mut services, &name, &content);
+                } else if name != MANIFEST_PATH && seen.insert(name.clone()) {
+                    zip.start_file(name, options).map_err(jar_error)?;
+                    zip.write_all(&content).map_err(io_error)?;
+                }
+            }
+        }
+
+        for jar in &self.merged_jars {
+            let file = File::open(jar)
+                .map_err(|e| RsjError::io(format!("Failed to open `{}`", jar.display()), e))?;
+            let mut archive = ZipArchive::new(file).map_err(jar_error)?;
+
+            for i in 0..archive.len() {
+                let mut entry = archive.by_index(i).map_err(jar_error)?;
+                let name = entry.name().to_string();
+
+                if name == MANIFEST_PATH || is_signature(&name) || name == "module-info.class" {
+                    continue;
+                }
+                if entry.is_dir() {
+                    if seen.insert(name.clone()) {
+                        zip.add_directory(name, options).map_err(jar_error)?;
+                    }
+                    continue;
+                }
+
+                let mut content = Vec::new();
+                entry.read_to_end(&mut content).map_err(io_error)?;
+                if name.starts_with(SERVICES_PREFIX) {
+                    append_service(&mut services, &name, &content);
+                } else if seen.insert(name.clone()) {
+                    zip.start_file(name, options).map_err(jar_error)?;
+                    zip.write_all(&content).map_err(io_error)?;
+                }
+            }
+        }
+
+        // Service files from every source are concatenated, like `mergeServiceFiles()`
+        if !services.is_empty() && seen.insert("META-INF/services/".to_string()) {
+            zip.add_directory("META-INF/services/", options)
+                .map_err(jar_error)?;
+        }
+        for (name, content) in services {
+            zip.start_file(name, options).map_err(jar_error)?;
+            zip.write_all(&content).map_err(io_error)?;
+        }
+
+        zip.finish().map_err(jar_error)?;
+        Ok(())
+    }
+
+    // Render the manifest, wrapping lines at 72 bytes as the JAR spec requires
+    fn manifest(&self) -> String {
+        let mut manifest = String::new();
+        for (name, value) in &self.attributes {
+            let line = format!("{}: {}", name, value);
+            let mut rest = line.as_str();
+            let mut limit = 72;
+            while rest.len() > limit {
+                let mut split = limit;
+                while !rest.is_char_boundary(split) {
+                    split -= 1;
+                }
+                manifest.push_str(&rest[..split]);
+                manifest.push_str("\r\n ");
+                rest = &rest[split..];
+                // Continuation lines start with a space, which counts towards the limit
+                limit = 71;
+            }
+            manifest.push_str(rest);
+            manifest.push_str("\r\n");
+        }
+        manifest.push_str("\r\n");
+        manifest
+    }
+}
+
+fn append_service(services: &mut BTreeMap<String, Vec<u8>>, name: &str, content: &[u8]) {
+    let merged = services.entry(name.to_string()).or_default();
+    if !merged.is_empty() && !merged.ends_with(b"\n") {
+        merged.push(b'\n');
+    }
+    merged.extend_from_slice(content);
+}
+
+// Signature files of signed dependencies would be invalid in the merged JAR
+fn is_signature(name: &str) -> bool {
+    name.starts_with("META-INF/")
+        && !name[9..].contains('/')
+        && [".SF", ".DSA", ".RSA", ".EC"]
+            .iter()
+            .any(|ext| name.to_ascii_uppercase().ends_with(ext))
+}
@@ -0,0 +1,355 @@
+// A small Java lexer, just capable enough to locate the package declaration of a
+// compilation unit. Only comments and annotations may legally precede it, so the
+// lexer understands those (including string, char and text block literals inside
+// annotation arguments) and stops at the first token of anything else.
+
+/// The `package ...;` declaration found in a Java source file.
+pub struct PackageDeclaration {
+    /// Fully qualified package name, with whitespace and comments removed.
+    pub name: String,
+    /// Byte offset of the `package` keyword.
+    pub start: usize,
+    /// Byte offset just past the terminating `;`.
+    pub end: usize,
+    /// 1-based line of the `package` keyword.
+    pub line: usize,
+}
+
+enum Token {
+    Ident(String),
+    Symbol(char),
+    Literal,
+}
+
+struct Lexer<'a> {
+    src: &'a str,
+    pos: usize,
+    line: usize,
+}
+
+impl<'a> Lexer<'a> {
+    fn new(src: &'a str) -> Self {
+        // Skip a leading UTF-8 byte order mark
+        let pos = if src.starts_with('\u{feff}') { 3 } else { 0 };
+        Lexer { src, pos, line: 1 }
+    }
+
+    fn peek_char(&self) -> Option<char> {
+        self.src[self.pos..].chars().next()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek_char()?;
+        self.pos += c.len_utf8();
+        if c == '\n' {
+            self.line += 1;
+        }
+        Some(c)
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.src[self.pos..]
+    }
+
+    // Skip whitespace and comments
+    fn skip_trivia(&mut self) -> Result<(), String> {
+        loop {
+            if self.rest().starts_with("//") {
+                while let Some(c) = self.peek_char() {
+                    if c == '\n' {
+                        break;
+                    }
+                    self.bump();
+                }
+            } else if self.rest().starts_with("/*") {
+                let start_line = self.line;
+                self.bump();
+                self.bump();
+                loop {
+                    if self.rest().starts_with("*/") {
+                        self.bump();
+                        self.bump();
+                        break;
+                    }
+                    if self.bump().is_none() {
+                        return Err(format!(
+                            "unterminated block comment starting on line {}",
+                            start_line
+                        ));
+                    }
+                }
+            } else if self.peek_char().is_some_and(char::is_whitespace) {
+                self.bump();
+            } else {
+                return Ok(());
+            }
+        }
+    }
+
+    fn skip_quoted(&mut self, quote: char) -> Result<(), String> {
+        let start_line = self.line;
+        self.bump();
+        loop {
+            match self.bump() {
+                Some('\\') => {
+                    self.bump();
+                }
+                Some(c) if c == quote => return Ok(()),
+                Some('\n') | None => {
+                    return Err(format!("unterminated literal on line {}", start_line));
+                }
+                Some(_) => {}
+            }
+        }
+    }
+
+    fn skip_text_block(&mut self) -> Result<(), String> {
+        let start_line = self.line;
+        for _ in 0..3 {
+            self.bump();
+        }
+        loop {
+            if self.rest().starts_with("\"\"\"") {
+                for _ in 0..3 {
+                    self.bump();
+                }
+                return Ok(());
+            }
+            match self.bump() {
+                Some('\\') => {
+                    self.bump();
+                }
+                Some(_) => {}
+                None => {
+                    return Err(format!(
+                        "unterminated text block starting on line {}",
+                        start_line
+                    ));
+                }
+            }
+        }
+    }
+
+    fn next_token(&mut self) -> Result<Option<Token>, String> {
+        self.skip_trivia()?;
+
+        let Some(c) = self.peek_char() else {
+            return Ok(None);
+        };
+
+        if self.rest().starts_with("\"\"\"") {
+            self.skip_text_block()?;
+            return Ok(Some(Token::Literal));
+        }
+        if c == '"' || c == '\'' {
+            self.skip_quoted(c)?;
+            return Ok(Some(Token::Literal));
+        }
+        if is_ident_start(c) {
+            let start = self.pos;
+            while self.peek_char().is_some_and(is_ident_part) {
+                self.bump();
+            }
+            return Ok(Some(Token::Ident(self.src[start..self.pos].to_string())));
+        }
+
+        self.bump();
+        Ok(Some(Token::Symbol(c)))
+    }
+
+    // Look at the next token without consuming it
+    fn peek_token(&mut self) -> Result<Option<Token>, String> {
+        let (pos, line) = (self.pos, self.line);
+        let token = self.next_token();
+        self.pos = pos;
+        self.line = line;
+        token
+    }
+
+    // Parse `Ident { . Ident }` and return the dotted name
+    fn qualified_name(&mut self) -> Result<Option<String>, String> {
+        let mut name = match self.next_token()? {
+            Some(Token::Ident(ident)) => ident,
+            _ => return Ok(None),
+        };
+        while let Some(Token::Symbol('.')) = self.peek_token()? {
+            self.next_token()?;
+            match self.next_token()? {
+                Some(Token::Ident(ident)) => {
+                    name.push('.');
+                    name.push_str(&ident);
+                }
+                _ => return Ok(None),
+            }
+        }
+        Ok(Some(name))
+    }
+
+    // Skip a parenthesised annotation argument list, the `(` has not been consumed yet
+    fn skip_parens(&mut self) -> Result<(), String> {
+        let start_line = self.line;
+        let mut depth = 0usize;
+        loop {
+            match self.next_token()? {
+                Some(Token::Symbol('(')) => depth += 1,
+                Some(Token::Symbol(')')) => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Ok(());
+                    }
+                }
+                Some(_) => {}
+                None => {
+                    return Err(format!(
+                        "unbalanced parentheses in annotation on line {}",
+                        start_line
+                    ));
+                }
+            }
+        }
+    }
+}
+
+fn is_ident_start(c: char) -> bool {
+    c.is_alphabetic() || c == '_' || c == '$'
+}
+
+fn is_ident_part(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '$'
+}
+
+// Reserved keywords and literals, none of which may be used as an identifier
+const RESERVED_WORDS: &[&str] = &[
+    "abstract",
+    "assert",
+    "boolean",
+    "break",
+    "byte",
+    "case",
+    "catch",
+    "char",
+    "class",
+    "const",
+    "continue",
+    "default",
+    "do",
+    "double",
+    "else",
+    "enum",
+    "extends",
+    "final",
+    "finally",
+    "float",
+    "for",
+    "goto",
+    "if",
+    "implements",
+    "import",
+    "instanceof",
+    "int",
+    "interface",
+    "long",
+    "native",
+    "new",
+    "package",
+    "private",
+    "protected",
+    "public",
+    "return",
+    "short",
+    "static",
+    "strictfp",
+    "super",
+    "switch",
+    "synchronized",
+    "this",
+    "throw",
+    "throws",
+    "transient",
+    "try",
+    "void",
+    "volatile",
+    "while",
+    "true",
+    "false",
+    "null",
+    "_",
+];
+
+/// Whether `name` is a valid Java identifier such as `Main` or `$proxy`.
+pub fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(is_ident_start)
+        && chars.all(is_ident_part)
+        && !RESERVED_WORDS.contains(&name)
+}
+
+/// Whether `name` is a dot-separated sequence of identifiers such as `com.example`.
+pub fn is_qualified_name(name: &str) -> bool {
+    name.split('.').all(is_identifier)
+}
+
+/// Find the package declaration of a Java compilation unit, skipping any leading
+/// comments and annotations. Returns `None` if the file has no package declaration.
+pub fn find_package_declaration(source: &str) -> Result<Option<PackageDeclaration>, String> {
+    let mut lexer = Lexer::new(source);
+
+    loop {
+        lexer.skip_trivia()?;
+        let (start, line) = (lexer.pos, lexer.line);
+
+        match lexer.next_token()? {
+            Some(Token::Symbol('@')) => {
+                // `@interface` starts an annotation type, not a package annotation
+                match lexer.qualified_name()? {
+                    Some(name) if name != "interface" => {}
+                    _ => return Ok(None),
+                }
+                if let Some(Token::Symbol('(')) = lexer.peek_token()? {
+                    lexer.skip_parens()?;
+                }
+            }
+            Some(Token::Ident(keyword)) if keyword == "package" => {
+                let name = lexer
+                    .qualified_name()?
+                    .ok_or_else(|| format!("malformed package declaration on line {}", line))?;
+                return match lexer.next_token()? {
+                    Some(Token::Symbol(';')) => Ok(Some(PackageDeclaration {
+                        name,
+                        start,
+                        end: lexer.pos,
+                        line,
+                    })),
+                    _ => Err(format!(
+                        "expected `;` after package declaration on line {}",
+                        line
+                    )),
+                };
+            }
+            _ => return Ok(None),
+        }
+    }
+}
+
+/// Replace (or insert) the package declaration of `source` with `package`, keeping
+/// every other line where it was so compiler diagnostics still line up.
+pub fn rewrite_package(source: &str, package: &str) -> Result<String, String> {
+    let source = source.strip_prefix('\u{feff}').unwrap_or(source);
+    let declaration = format!("package {};", package);
+
+    match find_package_declaration(source)? {
+        Some(existing) => {
+            // Keep the newlines of a declaration that spans several lines
+            let newlines = source[existing.start..existing.end].matches('\n').count();
+            Ok(format!(
+                "{}{}{}{}",
+                &source[..existing.start],
+                declaration,
+                "\n".repeat(newlines),
+                &source[existing.end..]
+            ))
+        }
+        // Prepend on the first line rather than adding a new one
+        None => Ok(format!("{} {}", declaration, source)),
+    }
+}
@@ -0,0 +1,430 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+use crate::artifacts;
+use crate::backend::{BuildBackend, DiagnosticStream, classified_jar, run_build_tool};
+use crate::build::project_root;
+use crate::build_info;
+use crate::config::{Config, Coordinates, DependencyKind, Scope};
+use crate::diagnostics::SourceMap;
+use crate::error::RsjError;
+use crate::fingerprint;
+use crate::jar::JarBuilder;
+use crate::jdk::{self, Jdk};
+use crate::lock::{Pin, ResolvedArtifact};
+use crate::resources::{RESOURCES_PATH, copy_resources};
+use crate::utils::{
+    FINGERPRINT_PATH, copy_src_files, home_dir, is_inline_test, printinfo, remove_stale_files,
+    slash_path, write_if_changed,
+};
+
+pub const JAVAC_PATH: &str = "javac";
+
+/// Builds by invoking `javac` directly and packaging the JAR in rsj itself.
+pub struct JavacBackend;
+
+impl BuildBackend for JavacBackend {
+    fn name(&self) -> &'static str {
+        "javac"
+    }
+
+    fn prepare(
+        &self,
+        config: &Config,
+        src_dir: &Path,
+        _tests_dir: &Path,
+        temp_path: &Path,
+        _pins: &[Pin],
+    ) -> Result<(), RsjError> {
+        let sources_dir = temp_path.join(JAVAC_PATH).join("src");
+        let namespace = &config.project.base_namespace;
+
+        // Copy source files with correct namespace
+        let inline_tests = config.inline_tests();
+        let namespace_dir = sources_dir.join(namespace.replace('.', "/"));
+        let mut written = copy_src_files(
+            src_dir.to_str().unwrap(),
+            &namespace_dir,
+            namespace,
+            |path| !(inline_tests && is_inline_test(path)),
+        )?;
+        written.extend(build_info::write_build_info(config, &namespace_dir)?);
+        remove_stale_files(&sources_dir, &written.into_iter().collect())?;
+
+        // Created even when empty, the build fingerprints and packs it
+        let resources_dir = temp_path.join(JAVAC_PATH).join(RESOURCES_PATH);
+        fs::create_dir_all(&resources_dir)
+            .map_err(|e| RsjError::io("Failed to create the resources directory", e))?;
+        let written = copy_resources(
+            config,
+            &project_root(config).join(RESOURCES_PATH),
+            &resources_dir,
+        )?;
+        remove_stale_files(&resources_dir, &written.into_iter().collect())
+    }
+
+    fn build(&self, config: &Config, temp_path: &Path) -> Result<(), RsjError> {
+        let javac_dir = temp_path.join(JAVAC_PATH);
+        let sources_dir = javac_dir.join("src");
+        let classes_dir = javac_dir.join("classes");
+        let use_shadow = config.use_shadow();
+
+        let paths = |artifacts: Vec<ResolvedArtifact>| -> Vec<PathBuf> {
+            artifacts
+                .into_iter()
+                .map(|artifact| artifact.path)
+                .collect()
+        };
+        let class_path = paths(resolve_dependencies(
+            config,
+            DependencyKind::Normal,
+            Scope::on_compile_classpath,
+        )?);
+        let runtime =
+            resolve_dependencies(config, DependencyKind::Normal, Scope::on_runtime_classpath)?;
+        let lib_paths = artifacts::lib_paths(&runtime);
+        let dependencies = paths(runtime);
+        let processor_path = paths(resolve_dependencies(config, DependencyKind::Build, |_| {
+            true
+        })?);
+
+        let jdk = jdk::project_jdk(config)?;
+
+        // Skip compilation if neither sources nor dependencies changed
+        let jar_path = self.jar_path(config, temp_path);
+        let fingerprint_dir = temp_path.join(FINGERPRINT_PATH);
+        let inputs = format!(
+            "{:?}\n{:?}\n{:?}\n{}\n{}\n{}\n{}\n{}\n{}",
+            config.project.main_class,
+            config.project.java_version,
+            jdk.as_ref().map(|jdk| &jdk.home),
+            use_shadow,
+            config.sources_jar(),
+            config.javadoc_jar(),
+            config.profile.debug,
+            config.profile.javac_args().join(" "),
+            [&class_path, &dependencies, &processor_path]
+                .iter()
+                .map(|jars| {
+                    jars.iter()
+                        .map(|jar| jar.display().to_string())
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        );
+        let resources_dir = javac_dir.join(RESOURCES_PATH);
+        let current = fingerprint::fingerprint_dir(&sources_dir, &inputs)?;
+        let current = fingerprint::fingerprint_dir(&resources_dir, &current)?;
+        if fingerprint::is_fresh(&fingerprint_dir, JAVAC_PATH, &current) && jar_path.exists() {
+            printinfo("Build is up to date.");
+            return Ok(());
+        }
+        fingerprint::clear(&fingerprint_dir, JAVAC_PATH);
+
+        // Compile from scratch so classes of deleted sources do not linger
+        if classes_dir.exists() {
+            fs::remove_dir_all(&classes_dir)
+                .map_err(|e| RsjError::io("Failed to clean the classes directory", e))?;
+        }
+        fs::create_dir_all(&classes_dir)
+            .map_err(|e| RsjError::io("Failed to create the classes directory", e))?;
+        compile(
+            config,
+            jdk.as_ref(),
+            &javac_dir,
+            &class_path,
+            &processor_path,
+        )?;
+
+        // Package the JAR, runnable unless the project is a library
+        let mut jar = JarBuilder::new().dir(&classes_dir).dir(&resources_dir);
+        if let Some(main_class) = config.main_class().filter(|_| !config.is_library()) {
+            jar = jar.attribute("Main-Class", &main_class);
+        }
+        if use_shadow {
+            for dependency in &dependencies {
+                jar = jar.merge_jar(dependency);
+            }
+        } else if !lib_paths.is_empty() {
+            // Thin JAR: the manifest refers to the dependencies in `lib/` next to it,
+            // where `rsj build` also puts them in `target/<profile>/`
+            let libs_dir = jar_path.parent().unwrap();
+            let mut class_path = Vec::new();
+            for (dependency, lib_path) in &lib_paths {
+                let destination = libs_dir.join(lib_path);
+                fs::create_dir_all(destination.parent().unwrap())
+                    .map_err(|e| RsjError::io("Failed to create the libs directory", e))?;
+                fs::copy(dependency, &destination).map_err(|e| {
+                    RsjError::io(format!("Failed to copy `{}`", dependency.display()), e)
+                })?;
+                class_path.push(slash_path(lib_path));
+            }
+            jar = jar.attribute("Class-Path", &class_path.join(" "));
+        }
+        jar.write(&jar_path)?;
+
+        if config.sources_jar() {
+            JarBuilder::new()
+                .dir(&sources_dir)
+                .write(&classified_jar(&jar_path, "sources"))?;
+        }
+        if config.javadoc_jar() {
+            let javadoc_dir = javac_dir.join("javadoc");
+            javadoc(config, jdk.as_ref(), &javac_dir, &class_path)?;
+            JarBuilder::new()
+                .dir(&javadoc_dir)
+                .write(&classified_jar(&jar_path, "javadoc"))?;
+        }
+
+        fingerprint::record(&fingerprint_dir, JAVAC_PATH, &current)?;
+        printinfo(&format!("Wrote {}", jar_path.display()));
+        Ok(())
+    }
+
+    fn jar_path(&self, config: &Config, temp_path: &Path) -> PathBuf {
+        temp_path.join(JAVAC_PATH).join("libs").join(format!(
+            "{}-{}.jar",
+            config.project.name, config.project.version
+        ))
+    }
+
+    fn resolved_artifacts(
+        &self,
+        config: &Config,
+        _temp_path: &Path,
+    ) -> Result<Vec<ResolvedArtifact>, RsjError> {
+        resolve_dependencies(config, DependencyKind::Normal, Scope::on_runtime_classpath)
+    }
+}
+
+fn compile(
+    config: &Config,
+    jdk: Option<&Jdk>,
+    javac_dir: &Path,
+    class_path: &[PathBuf],
+    processor_path: &[PathBuf],
+) -> Result<(), RsjError> {
+    let sources_dir = javac_dir.join("src");
+
+    // Pass sources through an argument file to stay clear of command line limits
+    let listing: Vec<String> = WalkDir::new(&sources_dir)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter(|e| e.file_type().is_file())
+        .map(|e| {
+            let relative = e.path().strip_prefix(javac_dir).unwrap();
+            format!("\"{}\"", relative.display().to_string().replace('\\', "/"))
+        })
+        .collect();
+    write_if_changed(&javac_dir.join("sources.txt"), &listing.join("\n"))?;
+
+    // A short-lived compiler JVM starts faster with C1 only and the serial collector
+    let mut args = vec![
+        "-J-XX:TieredStopAtLevel=1".to_string(),
+        "-J-XX:+UseSerialGC".to_string(),
+        "-d".to_string(),
+        "classes".to_string(),
+        "-encoding".to_string(),
+        "UTF-8".to_string(),
+        if config.profile.debug {
+            "-g"
+        } else {
+            "-g:none"
+        }
+        .to_string(),
+    ];
+    args.extend(config.profile.javac_args());
+    if let Some(java_version) = &config.project.java_version {
+        if let Some(jdk) = jdk
+            && jdk.version.to_string() != *java_version
+        {
+            printinfo(&format!(
+                "No JDK {} found, compiling for it with JDK {}.",
+                java_version, jdk.version
+            ));
+        }
+        args.push("--release".to_string());
+        args.push(java_version.clone());
+    }
+    for (option, jars) in [("-cp", class_path), ("-processorpath", processor_path)] {
+        if jars.is_empty() {
+            continue;
+        }
+        let joined = env::join_paths(jars)
+            .map_err(|e| RsjError::Config(format!("Invalid dependency path: {}", e)))?;
+        args.push(option.to_string());
+        args.push(joined.to_string_lossy().to_string());
+    }
+    args.push("@sources.txt".to_string());
+
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    run_build_tool(
+        &jdk::program(jdk, "javac"),
+        &args,
+        javac_dir,
+        &source_map(config, javac_dir),
+        DiagnosticStream::Stderr,
+    )
+}
+
+// Diagnostics about the copied sources point back at `src/`
+fn source_map(config: &Config, javac_dir: &Path) -> SourceMap {
+    let namespace_dir = javac_dir
+        .join("src")
+        .join(config.project.base_namespace.replace('.', "/"));
+    SourceMap::new(javac_dir).with_root(&namespace_dir, &project_root(config).join("src"))
+}
+
+// Generate the API documentation of the base namespace into `javac/javadoc`
+fn javadoc(
+    config: &Config,
+    jdk: Option<&Jdk>,
+    javac_dir: &Path,
+    class_path: &[PathBuf],
+) -> Result<(), RsjError> {
+    let javadoc_dir = javac_dir.join("javadoc");
+
+    // Start afresh so pages of deleted classes do not linger
+    if javadoc_dir.exists() {
+        fs::remove_dir_all(&javadoc_dir)
+            .map_err(|e| RsjError::io("Failed to clean the Javadoc directory", e))?;
+    }
+
+    // Doclint would fail the build over comment style alone
+    let mut args = vec![
+        "-quiet".to_string(),
+        "-Xdoclint:none".to_string(),
+        "-encoding".to_string(),
+        "UTF-8".to_string(),
+        "-d".to_string(),
+        "javadoc".to_string(),
+        "-sourcepath".to_string(),
+        "src".to_string(),
+        "-subpackages".to_string(),
+        config.project.base_namespace.clone(),
+    ];
+    if !class_path.is_empty() {
+        let joined = env::join_paths(class_path)
+            .map_err(|e| RsjError::Config(format!("Invalid dependency path: {}", e)))?;
+        args.push("-cp".to_string());
+        args.push(joined.to_string_lossy().to_string());
+    }
+
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    run_build_tool(
+        &jdk::program(jdk, "javadoc"),
+        &args,
+        javac_dir,
+        &source_map(config, javac_dir),
+        DiagnosticStream::Stderr,
+    )
+}
+
+// Find the JAR of every entry of a dependency table whose scope passes `include` in a
+// local repository. Only direct dependencies are used, the javac backend does not
+// read POM files, so `exclude` has nothing to do.
+fn resolve_dependencies(
+    config: &Config,
+    kind: DependencyKind,
+    include: impl Fn(Scope) -> bool,
+) -> Result<Vec<ResolvedArtifact>, RsjError> {
+    let repositories = local_repositories(config);
+
+    let mut resolved = Vec::new();
+    for (name, dependency) in config.dependency_table(kind) {
+        if !include(dependency.scope()) {
+            continue;
+        }
+        let coordinates = dependency.coordinates(name)?;
+        let Coordinates {
+            group,
+            artifact,
+            version,
+            classifier,
+        } = &coordinates;
+        let file_name = match classifier {
+            Some(classifier) => format!("{}-{}-{}.jar", artifact, version, classifier),
+            None => format!("{}-{}.jar", artifact, version),
+        };
+
+        let found = repositories.iter().find_map(|repository| {
+            find_in_repository(repository, group, artifact, version, &file_name)
+        });
+        match found {
+            // javac runs inside the build directory, so relative paths would break
+            Some(jar) => resolved.push(ResolvedArtifact {
+                group: group.clone(),
+                name: artifact.clone(),
+                version: version.clone(),
+                classifier: classifier.clone(),
+                path: jar.canonicalize().unwrap_or(jar),
+            }),
+            None => {
+                let searched: Vec<String> = repositories
+                    .iter()
+                    .map(|repository| format!("  {}", repository.display()))
+                    .collect();
+                return Err(RsjError::Config(format!(
+                    "Dependency `{}` ({}) was not found in a local repository. Searched:\n{}",
+                    name,
+                    coordinates.notation(),
+                    searched.join("\n")
+                )));
+            }
+        }
+    }
+
+    Ok(resolved)
+}
+
+// `local_repository` from rsj.toml first, then the Maven and Gradle caches
+fn local_repositories(config: &Config) -> Vec<PathBuf> {
+    let mut repositories = Vec::new();
+    if let Some(repository) = &config.project.local_repository {
+        repositories.push(config.manifest_dir.join(repository));
+    }
+    if let Some(home) = home_dir() {
+        repositories.push(home.join(".m2").join("repository"));
+        repositories.push(
+            home.join(".gradle")
+                .join("caches")
+                .join("modules-2")
+                .join("files-2.1"),
+        );
+    }
+    repositories
+}
+
+fn find_in_repository(
+    repository: &Path,
+    group: &str,
+    artifact: &str,
+    version: &str,
+    file_name: &str,
+) -> Option<PathBuf> {
+    // Maven layout: com/google/guava/guava/33.0-jre/guava-33.0-jre.jar
+    let maven = repository
+        .join(group.replace('.', "/"))
+        .join(artifact)
+        .join(version)
+        .join(file_name);
+    if maven.is_file() {
+        return Some(maven);
+    }
+
+    // Gradle cache layout: com.google.guava/guava/33.0-jre/<sha1>/guava-33.0-jre.jar
+    let gradle = repository.join(group).join(artifact).join(version);
+    let mut candidates: Vec<PathBuf> = fs::read_dir(gradle)
+        .ok()?
+        .filter_map(Result::ok)
+        .map(|entry| entry.path().join(file_name))
+        .filter(|path| path.is_file())
+        .collect();
+    candidates.sort();
+    candidates.into_iter().next()
+}
@@ -0,0 +1,187 @@
+// Finds installed JDKs, so `java_version` picks the compiler and runtime instead of
+// whichever JDK happens to be on PATH. Looks where JDKs are usually installed:
+// JAVA_HOME, the JDK of `javac` on PATH, the system directories and SDKMAN!.
+
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::config::Config;
+use crate::error::RsjError;
+use crate::utils::{find_program, home_dir};
+
+/// An installed JDK, described by its `release` file.
+pub struct Jdk {
+    pub home: PathBuf,
+    pub version: u32, // Feature release, e.g. `8` for `1.8.0_392`
+    pub vendor: Option<String>,
+}
+
+impl Jdk {
+    /// Path of one of the JDK's programs, e.g. `javac`.
+    pub fn tool(&self, name: &str) -> PathBuf {
+        self.home
+            .join("bin")
+            .join(format!("{}{}", name, env::consts::EXE_SUFFIX))
+    }
+
+    fn describe(&self) -> String {
+        match &self.vendor {
+            Some(vendor) => format!("{} ({}) {}", self.version, vendor, self.home.display()),
+            None => format!("{} {}", self.version, self.home.display()),
+        }
+    }
+}
+
+/// Every JDK found, in order of preference and without duplicates.
+pub fn installed_jdks() -> Vec<Jdk> {
+    let mut homes: Vec<PathBuf> = Vec::new();
+    if let Some(java_home) = env::var_os("JAVA_HOME") {
+        homes.push(PathBuf::from(java_home));
+    }
+    // `/usr/bin/javac` is usually a chain of links into the JDK
+    if let Some(javac) = find_program(&format!("javac{}", env::consts::EXE_SUFFIX))
+        && let Ok(javac) = javac.canonicalize()
+        && let Some(home) = javac.parent().and_then(Path::parent)
+    {
+        homes.push(home.to_path_buf());
+    }
+    homes.extend(subdirectories(Path::new("/usr/lib/jvm")));
+    homes.extend(
+        subdirectories(Path::new("/Library/Java/JavaVirtualMachines"))
+            .into_iter()
+            .map(|bundle| bundle.join("Contents/Home")),
+    );
+    let sdkman = env::var_os("SDKMAN_DIR")
+        .map(PathBuf::from)
+        .or_else(|| home_dir().map(|home| home.join(".sdkman")));
+    if let Some(sdkman) = sdkman {
+        homes.extend(subdirectories(&sdkman.join("candidates/java")));
+    }
+
+    let mut seen = Vec::new();
+    let mut jdks = Vec::new();
+    for home in homes {
+        let Ok(canonical) = home.canonicalize() else {
+            continue;
+        };
+        if seen.contains(&canonical) {
+            continue;
+        }
+        seen.push(canonical);
+        if let Some(jdk) = read_jdk(&home) {
+            jdks.push(jdk);
+        }
+    }
+    jdks
+}
+
+/// The JDK for `java_version` and `vendor` of rsj.toml, or `None` to use the
+/// programs on PATH. Without a JDK of that version, a newer one compiles for it
+/// with `--release`.
+pub fn project_jdk(config: &Config) -> Result<Option<Jdk>, RsjError> {
+    let Some(java_version) = &config.project.java_version else {
+        return Ok(None);
+    };
+    // Validated to be a number
+    let version: u32 = java_version.parse().unwrap_or_default();
+    let vendor = config.project.vendor.as_deref();
+
+    let mut jdks = installed_jdks();
+    let usable = |jdk: &Jdk| vendor.is_none_or(|vendor| matches_vendor(jdk, vendor));
+    let exact = jdks
+        .iter()
+        .position(|jdk| usable(jdk) && jdk.version == version);
+    let newer = jdks
+        .iter()
+        .enumerate()
+        .filter(|(_, jdk)| usable(jdk) && jdk.version > version)
+        .min_by_key(|(_, jdk)| jdk.version)
+        .map(|(index, _)| index);
+    if let Some(index) = exact.or(newer) {
+        return Ok(Some(jdks.swap_remove(index)));
+    }
+
+    let wanted = match vendor {
+        Some(vendor) => format!("JDK {} from `{}`", version, vendor),
+        None => format!("JDK {}", version),
+    };
+    let installed = if jdks.is_empty() {
+        "No JDKs were found in JAVA_HOME, on PATH, in /usr/lib/jvm or in ~/.sdkman.".to_string()
+    } else {
+        let list: Vec<String> = jdks
+            .iter()
+            .map(|jdk| format!("  {}", jdk.describe()))
+            .collect();
+        format!("Installed JDKs:\n{}", list.join("\n"))
+    };
+    Err(RsjError::tool_not_found(
+        "javac",
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            format!(
+                "{} is required by `java_version`, but it is not installed. {}\nInstall it, or point JAVA_HOME at it.",
+                wanted, installed
+            ),
+        ),
+    ))
+}
+
+/// The program `name` of `jdk`, or the one on PATH without a JDK.
+pub fn program(jdk: Option<&Jdk>, name: &str) -> String {
+    match jdk {
+        Some(jdk) => jdk.tool(name).display().to_string(),
+        None => name.to_string(),
+    }
+}
+
+// `vendor = "adoptium"` matches `IMPLEMENTOR="Eclipse Adoptium"`, like Gradle's
+// `JvmVendorSpec.matching`
+fn matches_vendor(jdk: &Jdk, vendor: &str) -> bool {
+    jdk.vendor
+        .as_ref()
+        .is_some_and(|implementor| implementor.to_lowercase().contains(&vendor.to_lowercase()))
+}
+
+// A JDK home has a `release` file and a compiler, a JRE only the former
+fn read_jdk(home: &Path) -> Option<Jdk> {
+    let release = fs::read_to_string(home.join("release")).ok()?;
+    let jdk = Jdk {
+        home: home.to_path_buf(),
+        version: feature_version(&release_value(&release, "JAVA_VERSION")?)?,
+        vendor: release_value(&release, "IMPLEMENTOR"),
+    };
+    jdk.tool("javac").is_file().then_some(jdk)
+}
+
+// `KEY="value"` lines of a `release` file
+fn release_value(release: &str, key: &str) -> Option<String> {
+    release.lines().find_map(|line| {
+        let value = line.strip_prefix(key)?.strip_prefix('=')?;
+        Some(value.trim().trim_matches('"').to_string())
+    })
+}
+
+// `1.8.0_392` is 8, `17.0.15` is 17
+fn feature_version(version: &str) -> Option<u32> {
+    let version = version.strip_prefix("1.").unwrap_or(version);
+    version
+        .split(|c: char| !c.is_ascii_digit())
+        .next()?
+        .parse()
+        .ok()
+}
+
+fn subdirectories(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut dirs: Vec<PathBuf> = entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir())
+        .collect();
+    dirs.sort();
+    dirs
+}
@@ -0,0 +1,295 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::config::{Config, DependencyKind};
+use crate::error::RsjError;
+use crate::fingerprint::sha256_hex;
+use crate::utils::{printinfo, write_if_changed};
+
+pub const LOCK_PATH: &str = "rsj.lock";
+
+const LOCK_HEADER: &str =
+    "# This file is generated by rsj. It is not intended for manual editing.\n";
+
+/// How a command treats `rsj.lock`.
+#[derive(Clone, PartialEq, Eq)]
+pub enum LockMode {
+    /// Follow the lockfile, and update it when `[dependencies]` changed.
+    Normal,
+    /// Fail instead of changing the lockfile (`--locked`).
+    Locked,
+    /// Resolve again, ignoring the lockfile for all or one dependency (`rsj update`).
+    Update(Option<String>),
+}
+
+/// An artifact on the runtime classpath, as resolved by a backend.
+pub struct ResolvedArtifact {
+    pub group: String,
+    pub name: String,
+    pub version: String,
+    pub classifier: Option<String>,
+    pub path: PathBuf,
+}
+
+/// A version a backend has to resolve an artifact to. A `strict` pin fails the
+/// build if anything needs another version, otherwise the pin is only preferred.
+pub struct Pin {
+    pub group: String,
+    pub name: String,
+    pub version: String,
+    pub strict: bool,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq)]
+struct LockedArtifact {
+    group: String,
+    name: String,
+    version: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    classifier: Option<String>,
+    checksum: String,
+}
+
+impl LockedArtifact {
+    // Identifies the artifact across versions
+    fn key(&self) -> String {
+        match &self.classifier {
+            Some(classifier) => format!("{}:{}:{}", self.group, self.name, classifier),
+            None => format!("{}:{}", self.group, self.name),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq)]
+struct Lockfile {
+    version: u32,
+    // The `[dependencies]` of rsj.toml the lockfile was resolved from
+    #[serde(default)]
+    dependencies: BTreeMap<String, String>,
+    // Only the declarations of these two: their artifacts are not resolved for the
+    // lockfile, but `--locked` still notices when they change
+    #[serde(
+        default,
+        rename = "dev-dependencies",
+        skip_serializing_if = "BTreeMap::is_empty"
+    )]
+    dev_dependencies: BTreeMap<String, String>,
+    #[serde(
+        default,
+        rename = "build-dependencies",
+        skip_serializing_if = "BTreeMap::is_empty"
+    )]
+    build_dependencies: BTreeMap<String, String>,
+    #[serde(default, rename = "artifact", skip_serializing_if = "Vec::is_empty")]
+    artifacts: Vec<LockedArtifact>,
+}
+
+fn lock_path(config: &Config) -> PathBuf {
+    config.manifest_dir.join(LOCK_PATH)
+}
+
+fn declared_dependencies(config: &Config, kind: DependencyKind) -> BTreeMap<String, String> {
+    config
+        .dependency_table(kind)
+        .into_iter()
+        .map(|(name, dependency)| (name.clone(), dependency.describe()))
+        .collect()
+}
+
+impl Lockfile {
+    fn declared(&self, kind: DependencyKind) -> &BTreeMap<String, String> {
+        match kind {
+            DependencyKind::Normal => &self.dependencies,
+            DependencyKind::Dev => &self.dev_dependencies,
+            DependencyKind::Build => &self.build_dependencies,
+        }
+    }
+}
+
+fn load(config: &Config) -> Result<Option<Lockfile>, RsjError> {
+    let path = lock_path(config);
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let content = fs::read_to_string(&path)
+        .map_err(|e| RsjError::io(format!("Failed to read `{}`", LOCK_PATH), e))?;
+    toml::from_str(&content)
+        .map(Some)
+        .map_err(|e| RsjError::Lock(format!("Invalid `{}`: {}", LOCK_PATH, e)))
+}
+
+/// The versions the next resolution has to stick to.
+pub fn pins(config: &Config, mode: &LockMode) -> Result<Vec<Pin>, RsjError> {
+    let lock = load(config)?;
+    // The tables of rsj.toml that differ from the lockfile
+    let changed: Vec<DependencyKind> = DependencyKind::ALL
+        .into_iter()
+        .filter(|&kind| {
+            lock.as_ref()
+                .is_none_or(|lock| *lock.declared(kind) != declared_dependencies(config, kind))
+        })
+        .collect();
+    let up_to_date = !changed.contains(&DependencyKind::Normal);
+
+    // Pin every locked artifact for which `keep` returns true
+    let pin_locked = |strict: bool, keep: &dyn Fn(&LockedArtifact) -> bool| {
+        lock.iter()
+            .flat_map(|lock| &lock.artifacts)
+            .filter(|artifact| keep(artifact))
+            .map(|artifact| Pin {
+                group: artifact.group.clone(),
+                name: artifact.name.clone(),
+                version: artifact.version.clone(),
+                strict,
+            })
+            .collect()
+    };
+
+    match mode {
+        LockMode::Locked if lock.is_none() => Err(RsjError::Lock(format!(
+            "`{}` does not exist, but `--locked` was passed. Run `rsj build` to create it.",
+            LOCK_PATH
+        ))),
+        LockMode::Locked if !changed.is_empty() => {
+            let tables: Vec<String> = changed
+                .iter()
+                .map(|kind| format!("`[{}]`", kind.table_name()))
+                .collect();
+            Err(RsjError::Lock(format!(
+                "`{}` needs to be updated because {} changed, but `--locked` was passed.",
+                LOCK_PATH,
+                tables.join(" and ")
+            )))
+        }
+        LockMode::Update(None) => Ok(Vec::new()),
+        LockMode::Update(Some(package)) => {
+            let Some(dependency) = DependencyKind::ALL.into_iter().find_map(|kind| {
+                config
+                    .dependency_table(kind)
+                    .into_iter()
+                    .find_map(|(name, dependency)| (name == package).then_some(dependency))
+            }) else {
+                return Err(RsjError::Config(format!(
+                    "`{}` is not a dependency in rsj.toml.",
+                    package
+                )));
+            };
+            let updated = dependency.coordinates(package)?;
+            Ok(pin_locked(false, &|artifact| {
+                artifact.group != updated.group || artifact.name != updated.artifact
+            }))
+        }
+        // Edited dependencies may need newer versions of locked transitive ones
+        _ => Ok(pin_locked(up_to_date, &|_| true)),
+    }
+}
+
+/// Compare what the backend resolved with `rsj.lock` and update the lockfile,
+/// unless `--locked` forbids it. A locked artifact whose content changed is always
+/// an error.
+pub fn record(
+    config: &Config,
+    mode: &LockMode,
+    resolved: &[ResolvedArtifact],
+) -> Result<(), RsjError> {
+    let mut artifacts = Vec::new();
+    for artifact in resolved {
+        let content = fs::read(&artifact.path).map_err(|e| {
+            RsjError::io(format!("Failed to read `{}`", artifact.path.display()), e)
+        })?;
+        artifacts.push(LockedArtifact {
+            group: artifact.group.clone(),
+            name: artifact.name.clone(),
+            version: artifact.version.clone(),
+            classifier: artifact.classifier.clone(),
+            checksum: format!("sha256:{}", sha256_hex(&content)),
+        });
+    }
+    artifacts.sort_by_key(|artifact| (artifact.key(), artifact.version.clone()));
+    artifacts.dedup();
+
+    let lockfile = Lockfile {
+        version: 1,
+        dependencies: declared_dependencies(config, DependencyKind::Normal),
+        dev_dependencies: declared_dependencies(config, DependencyKind::Dev),
+        build_dependencies: declared_dependencies(config, DependencyKind::Build),
+        artifacts,
+    };
+    let old = load(config)?;
+
+    if let Some(old) = &old {
+        for artifact in &lockfile.artifacts {
+            if let Some(locked) = old
+                .artifacts
+                .iter()
+                .find(|locked| locked.key() == artifact.key() && locked.version == artifact.version)
+                && locked.checksum != artifact.checksum
+            {
+                return Err(RsjError::Lock(format!(
+                    "Checksum of `{}:{}` does not match `{}`.\n  expected: {}\n  found:    {}",
+                    artifact.key(),
+                    artifact.version,
+                    LOCK_PATH,
+                    locked.checksum,
+                    artifact.checksum
+                )));
+            }
+        }
+    }
+
+    if old.as_ref() == Some(&lockfile) {
+        return Ok(());
+    }
+
+    let changes = old
+        .as_ref()
+        .map(|old| describe_changes(old, &lockfile))
+        .unwrap_or_default();
+    if *mode == LockMode::Locked {
+        return Err(RsjError::Lock(format!(
+            "The resolved dependencies do not match `{}`, but `--locked` was passed.\n{}",
+            LOCK_PATH,
+            changes.join("\n")
+        )));
+    }
+
+    match &old {
+        Some(_) => changes.iter().for_each(|change| printinfo(change)),
+        None => printinfo(&format!("Writing {}", LOCK_PATH)),
+    }
+
+    let content = toml::to_string(&lockfile)
+        .map_err(|e| RsjError::Lock(format!("Failed to write `{}`: {}", LOCK_PATH, e)))?;
+    write_if_changed(&lock_path(config), &format!("{}{}", LOCK_HEADER, content))
+}
+
+// Cargo-style `Adding`/`Updating`/`Removing` lines between two lockfiles
+fn describe_changes(old: &Lockfile, new: &Lockfile) -> Vec<String> {
+    let versions = |lock: &Lockfile| -> BTreeMap<String, String> {
+        lock.artifacts
+            .iter()
+            .map(|artifact| (artifact.key(), artifact.version.clone()))
+            .collect()
+    };
+    let (old, new) = (versions(old), versions(new));
+
+    let mut changes = Vec::new();
+    for (key, version) in &new {
+        match old.get(key) {
+            None => changes.push(format!("Adding {} {}", key, version)),
+            Some(previous) if previous != version => {
+                changes.push(format!("Updating {} {} -> {}", key, previous, version))
+            }
+            _ => {}
+        }
+    }
+    for (key, version) in &old {
+        if !new.contains_key(key) {
+            changes.push(format!("Removing {} {}", key, version));
+        }
+    }
+    changes
+}
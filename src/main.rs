@@ -1,35 +1,101 @@
+mod artifacts;
+mod backend;
+mod binaries;
 mod build;
+mod build_info;
 mod commands;
 mod config;
+mod diagnostics;
+mod error;
+mod fingerprint;
 mod gradle;
+mod init;
+mod jar;
+mod java;
+mod javac;
+mod jdk;
+mod lock;
+mod maven;
+mod resources;
 mod run;
+mod template;
+mod testing;
 mod utils;
+mod validation;
 
 use clap::Parser;
 use commands::Commands;
+use config::load_config;
+use lock::LockMode;
+use std::path::{Path, PathBuf};
+use std::process;
 use utils::{printerr, separator};
 
 #[derive(Parser)]
 #[clap(name = "rsj", version = "0.1.0", author = "")]
 struct Cli {
+    /// Path to `rsj.toml`, instead of searching the current and parent directories
+    #[clap(long, global = true)]
+    manifest_path: Option<PathBuf>,
     #[clap(subcommand)]
     command: Commands,
 }
 
+fn lock_mode(locked: bool) -> LockMode {
+    if locked {
+        LockMode::Locked
+    } else {
+        LockMode::Normal
+    }
+}
+
 fn main() {
     let cli = Cli::parse();
 
     separator();
 
+    // Every command but `new` and `init` works on an existing project
+    let config = || load_config(cli.manifest_path.as_deref());
     let result = match cli.command {
-        Commands::Build => build::build_project(),
-        Commands::Run => run::run_project(),
-        Commands::Clean => build::clean_build(),
-        Commands::Init => build::init_project(),
+        Commands::Build { locked, profile } => config().and_then(|mut config| {
+            config.select_profile(profile.name())?;
+            build::build_project(&config, &lock_mode(locked)).map(|_| ())
+        }),
+        Commands::Run {
+            locked,
+            profile,
+            bin,
+            jvm_args,
+            args,
+        } => config().and_then(|mut config| {
+            config.select_profile(profile.name())?;
+            run::run_project(
+                &config,
+                &lock_mode(locked),
+                bin.as_deref(),
+                &jvm_args,
+                &args,
+            )
+        }),
+        Commands::Test {
+            filter,
+            nocapture,
+            locked,
+        } => config().and_then(|config| {
+            testing::test_project(&config, filter, nocapture, &lock_mode(locked))
+        }),
+        Commands::Update { package } => config().and_then(|config| {
+            build::build_project(&config, &LockMode::Update(package)).map(|_| ())
+        }),
+        Commands::Clean => config().and_then(|config| build::clean_build(&config)),
+        Commands::New { path, options } => init::new_project(&path, &options),
+        Commands::Init { options } => init::init_project(Path::new("."), &options),
     };
 
     if let Err(e) = result {
-        printerr(&e);
+        printerr(&e.to_string());
+        separator();
+        process::exit(e.exit_code());
     }
 
     separator();
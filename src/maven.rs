@@ -0,0 +1,454 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::backend::{
+    BuildBackend, DiagnosticStream, copy_standard_layout, run_build_tool, standard_source_map,
+};
+use crate::config::{Config, Dependency, DependencyKind, Scope};
+use crate::error::RsjError;
+use crate::fingerprint;
+use crate::lock::{Pin, ResolvedArtifact};
+use crate::utils::{FINGERPRINT_PATH, printinfo, write_if_changed};
+
+pub const MAVEN_PATH: &str = "maven";
+
+// Pinned so the `dependency:list` output format does not change under us
+const DEPENDENCY_LIST_GOAL: &str = "org.apache.maven.plugins:maven-dependency-plugin:3.6.1:list";
+
+// File below `target/` that `dependency:list` writes the runtime classpath to
+const RESOLVED_PATH: &str = "rsj-resolved.txt";
+
+/// Builds through a generated Maven project in `rsj_build/maven`.
+pub struct MavenBackend;
+
+impl BuildBackend for MavenBackend {
+    fn name(&self) -> &'static str {
+        "maven"
+    }
+
+    fn prepare(
+        &self,
+        config: &Config,
+        src_dir: &Path,
+        tests_dir: &Path,
+        temp_path: &Path,
+        pins: &[Pin],
+    ) -> Result<(), RsjError> {
+        let maven_dir = temp_path.join(MAVEN_PATH);
+        fs::create_dir_all(&maven_dir)
+            .map_err(|e| RsjError::io("Failed to create the Maven project directory", e))?;
+
+        copy_standard_layout(config, src_dir, tests_dir, &maven_dir)?;
+        write_if_changed(&maven_dir.join("pom.xml"), &generate_pom(config, pins)?)
+    }
+
+    fn build(&self, config: &Config, temp_path: &Path) -> Result<(), RsjError> {
+        // Skip Maven entirely if nothing changed since the last successful build
+        let maven_dir = temp_path.join(MAVEN_PATH);
+        let fingerprint_dir = temp_path.join(FINGERPRINT_PATH);
+        let current = fingerprint::fingerprint_project(&maven_dir, "package")?;
+        if fingerprint::is_fresh(&fingerprint_dir, MAVEN_PATH, &current)
+            && self.jar_path(config, temp_path).exists()
+        {
+            printinfo("Build is up to date.");
+            return Ok(());
+        }
+
+        fingerprint::clear(&fingerprint_dir, MAVEN_PATH);
+        let output_file = format!("-DoutputFile=target/{}", RESOLVED_PATH);
+        run_maven(
+            config,
+            temp_path,
+            &[
+                "package",
+                "-DskipTests",
+                DEPENDENCY_LIST_GOAL,
+                &output_file,
+                "-DoutputAbsoluteArtifactFilename=true",
+                "-DincludeScope=runtime",
+            ],
+        )?;
+        fingerprint::record(&fingerprint_dir, MAVEN_PATH, &current)
+    }
+
+    fn jar_path(&self, config: &Config, temp_path: &Path) -> PathBuf {
+        temp_path.join(MAVEN_PATH).join("target").join(format!(
+            "{}-{}.jar",
+            config.project.name, config.project.version
+        ))
+    }
+
+    fn resolved_artifacts(
+        &self,
+        _config: &Config,
+        temp_path: &Path,
+    ) -> Result<Vec<ResolvedArtifact>, RsjError> {
+        let path = temp_path
+            .join(MAVEN_PATH)
+            .join("target")
+            .join(RESOLVED_PATH);
+        let content = fs::read_to_string(&path)
+            .map_err(|e| RsjError::io("Maven did not report the resolved dependencies", e))?;
+        Ok(content.lines().filter_map(parse_dependency_line).collect())
+    }
+
+    fn test_results_dir(&self, temp_path: &Path) -> Option<PathBuf> {
+        Some(
+            temp_path
+                .join(MAVEN_PATH)
+                .join("target")
+                .join("surefire-reports"),
+        )
+    }
+
+    fn run_tests(
+        &self,
+        config: &Config,
+        temp_path: &Path,
+        filter: Option<&str>,
+        nocapture: bool,
+    ) -> Result<(), RsjError> {
+        // Cargo-style substring filter on the fully qualified test name
+        let pattern = filter.map(|f| format!("-Dtest=*{}*", f));
+        let mut args = vec!["test"];
+        if let Some(pattern) = &pattern {
+            args.push(pattern.as_str());
+        }
+        if nocapture {
+            args.push("-Drsj.capture=false");
+        }
+
+        run_maven(config, temp_path, &args)
+    }
+}
+
+// Run Maven in batch mode with the given arguments in the generated project
+fn run_maven(config: &Config, temp_path: &Path, args: &[&str]) -> Result<(), RsjError> {
+    let maven_dir = temp_path.join(MAVEN_PATH);
+    let mut all_args = vec!["-B", "--no-transfer-progress"];
+    all_args.extend_from_slice(args);
+
+    // Maven logs compiler diagnostics on stdout
+    run_build_tool(
+        "mvn",
+        &all_args,
+        &maven_dir,
+        &standard_source_map(config, &maven_dir),
+        DiagnosticStream::Stdout,
+    )
+}
+
+// Parse `group:artifact:type[:classifier]:version:scope:/path/to.jar`, as listed by
+// `dependency:list`, which may append ` -- module name` to the line
+fn parse_dependency_line(line: &str) -> Option<ResolvedArtifact> {
+    const SCOPES: [&str; 5] = ["compile", "runtime", "provided", "system", "test"];
+
+    let line = line.trim();
+    let line = line.split(" -- ").next().unwrap_or(line);
+    let parts: Vec<&str> = line.split(':').collect();
+    let scope = (4..parts.len()).find(|&i| SCOPES.contains(&parts[i]))?;
+    let path = parts[scope + 1..].join(":");
+    let (group, name, classifier, version) = match parts[..scope] {
+        [group, name, _, version] => (group, name, None, version),
+        [group, name, _, classifier, version] => (group, name, Some(classifier), version),
+        _ => return None,
+    };
+
+    Some(ResolvedArtifact {
+        group: group.to_string(),
+        name: name.to_string(),
+        version: version.to_string(),
+        classifier: classifier.map(str::to_string),
+        path: PathBuf::from(path),
+    })
+}
+
+// Render one `<dependency>` element with its scope and exclusions
+fn pom_dependency(
+    name: &str,
+    dependency: &Dependency,
+    scope: Option<&str>,
+) -> Result<String, RsjError> {
+    let coordinates = dependency.coordinates(name)?;
+    let mut element = format!(
+        r#"    <dependency>
+      <groupId>{}</groupId>
+      <artifactId>{}</artifactId>
+      <version>{}</version>
+"#,
+        xml_escape(&coordinates.group),
+        xml_escape(&coordinates.artifact),
+        xml_escape(&coordinates.version)
+    );
+    if let Some(classifier) = &coordinates.classifier {
+        element.push_str(&format!(
+            "      <classifier>{}</classifier>\n",
+            xml_escape(classifier)
+        ));
+    }
+    if let Some(scope) = scope {
+        element.push_str(&format!("      <scope>{}</scope>\n", scope));
+    }
+    if dependency.optional() {
+        element.push_str("      <optional>true</optional>\n");
+    }
+
+    let exclusions = dependency.exclusions(name)?;
+    if !exclusions.is_empty() {
+        element.push_str("      <exclusions>\n");
+        for exclusion in exclusions {
+            element.push_str(&format!(
+                "        <exclusion>\n          <groupId>{}</groupId>\n          <artifactId>{}</artifactId>\n        </exclusion>\n",
+                xml_escape(&exclusion.group),
+                xml_escape(exclusion.artifact.as_deref().unwrap_or("*"))
+            ));
+        }
+        element.push_str("      </exclusions>\n");
+    }
+
+    element.push_str("    </dependency>\n");
+    Ok(element)
+}
+
+// Escape text for use inside an XML element
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+// A plugin that attaches an extra JAR, such as the sources, during `package`
+fn attached_jar_plugin(artifact: &str, version: &str, goal: &str) -> String {
+    format!(
+        r#"      <plugin>
+        <groupId>org.apache.maven.plugins</groupId>
+        <artifactId>{artifact}</artifactId>
+        <version>{version}</version>
+        <executions>
+          <execution>
+            <goals>
+              <goal>{goal}</goal>
+            </goals>
+          </execution>
+        </executions>
+      </plugin>
+"#
+    )
+}
+
+fn generate_pom(config: &Config, pins: &[Pin]) -> Result<String, RsjError> {
+    let project = &config.project;
+    let use_shadow = config.use_shadow();
+    // Libraries have no entry point of their own
+    let main_class = config
+        .main_class()
+        .filter(|_| !config.is_library())
+        .map(|main_class| xml_escape(&main_class));
+
+    let mut dependencies = String::new();
+    for kind in [DependencyKind::Normal, DependencyKind::Dev] {
+        for (name, dependency) in config.dependency_table(kind) {
+            let scope = match (kind, dependency.scope()) {
+                (DependencyKind::Dev, _) => Some("test"),
+                (_, Scope::CompileOnly) => Some("provided"),
+                (_, Scope::RuntimeOnly) => Some("runtime"),
+                _ => None,
+            };
+            dependencies.push_str(&pom_dependency(name, dependency, scope)?);
+        }
+    }
+
+    // Annotation processors only run inside the compiler plugin
+    let mut processor_paths = String::new();
+    for (name, dependency) in config.dependency_table(DependencyKind::Build) {
+        let coordinates = dependency.coordinates(name)?;
+        processor_paths.push_str(&format!(
+            r#"            <path>
+              <groupId>{}</groupId>
+              <artifactId>{}</artifactId>
+              <version>{}</version>
+"#,
+            xml_escape(&coordinates.group),
+            xml_escape(&coordinates.artifact),
+            xml_escape(&coordinates.version)
+        ));
+        if let Some(classifier) = &coordinates.classifier {
+            processor_paths.push_str(&format!(
+                "              <classifier>{}</classifier>\n",
+                xml_escape(classifier)
+            ));
+        }
+        processor_paths.push_str("            </path>\n");
+    }
+    let mut compiler_configuration = String::new();
+    if !processor_paths.is_empty() {
+        compiler_configuration.push_str(&format!(
+            "          <annotationProcessorPaths>\n{processor_paths}          </annotationProcessorPaths>\n"
+        ));
+    }
+    // The profile's javac options, e.g. `-Xlint:all`
+    let javac_args = config.profile.javac_args();
+    if !javac_args.is_empty() {
+        compiler_configuration.push_str("          <compilerArgs>\n");
+        for arg in &javac_args {
+            compiler_configuration
+                .push_str(&format!("            <arg>{}</arg>\n", xml_escape(arg)));
+        }
+        compiler_configuration.push_str("          </compilerArgs>\n");
+    }
+    let compiler_plugin = if compiler_configuration.is_empty() {
+        String::new()
+    } else {
+        format!(
+            r#"      <plugin>
+        <groupId>org.apache.maven.plugins</groupId>
+        <artifactId>maven-compiler-plugin</artifactId>
+        <version>3.11.0</version>
+        <configuration>
+{compiler_configuration}        </configuration>
+      </plugin>
+"#
+        )
+    };
+
+    // Maven has no soft pins, managed versions always win over transitive ones
+    let mut managed = String::new();
+    if !pins.is_empty() {
+        managed.push_str("  <dependencyManagement>\n    <dependencies>\n");
+        for pin in pins {
+            managed.push_str(&format!(
+                r#"      <dependency>
+        <groupId>{}</groupId>
+        <artifactId>{}</artifactId>
+        <version>{}</version>
+      </dependency>
+"#,
+                xml_escape(&pin.group),
+                xml_escape(&pin.name),
+                xml_escape(&pin.version)
+            ));
+        }
+        managed.push_str("    </dependencies>\n  </dependencyManagement>\n\n");
+    }
+
+    let jar_manifest = match &main_class {
+        Some(main_class) => format!(
+            r#"
+        <configuration>
+          <archive>
+            <manifest>
+              <mainClass>{main_class}</mainClass>
+            </manifest>
+          </archive>
+        </configuration>"#
+        ),
+        None => String::new(),
+    };
+
+    let mut attached_plugins = String::new();
+    if config.sources_jar() {
+        attached_plugins.push_str(&attached_jar_plugin(
+            "maven-source-plugin",
+            "3.3.0",
+            "jar-no-fork",
+        ));
+    }
+    if config.javadoc_jar() {
+        attached_plugins.push_str(&attached_jar_plugin("maven-javadoc-plugin", "3.6.3", "jar"));
+    }
+
+    let shade_plugin = if let Some(main_class) = main_class.as_ref().filter(|_| use_shadow) {
+        format!(
+            r#"      <plugin>
+        <groupId>org.apache.maven.plugins</groupId>
+        <artifactId>maven-shade-plugin</artifactId>
+        <version>3.5.1</version>
+        <executions>
+          <execution>
+            <phase>package</phase>
+            <goals>
+              <goal>shade</goal>
+            </goals>
+            <configuration>
+              <createDependencyReducedPom>false</createDependencyReducedPom>
+              <transformers>
+                <transformer implementation="org.apache.maven.plugins.shade.resource.ServicesResourceTransformer"/>
+                <transformer implementation="org.apache.maven.plugins.shade.resource.ManifestResourceTransformer">
+                  <mainClass>{main_class}</mainClass>
+                </transformer>
+              </transformers>
+            </configuration>
+          </execution>
+        </executions>
+      </plugin>
+"#
+        )
+    } else {
+        String::new()
+    };
+
+    Ok(format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<project xmlns="http://maven.apache.org/POM/4.0.0"
+         xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance"
+         xsi:schemaLocation="http://maven.apache.org/POM/4.0.0 https://maven.apache.org/xsd/maven-4.0.0.xsd">
+  <modelVersion>4.0.0</modelVersion>
+
+  <groupId>{group}</groupId>
+  <artifactId>{artifact}</artifactId>
+  <version>{version}</version>
+  <packaging>jar</packaging>
+
+  <properties>
+    <project.build.sourceEncoding>UTF-8</project.build.sourceEncoding>
+    <rsj.capture>true</rsj.capture>
+{release}{debug}  </properties>
+
+{managed}  <dependencies>
+{dependencies}    <dependency>
+      <groupId>org.junit.jupiter</groupId>
+      <artifactId>junit-jupiter</artifactId>
+      <version>5.8.2</version>
+      <scope>test</scope>
+    </dependency>
+  </dependencies>
+
+  <build>
+    <plugins>
+{compiler_plugin}      <plugin>
+        <groupId>org.apache.maven.plugins</groupId>
+        <artifactId>maven-jar-plugin</artifactId>
+        <version>3.3.0</version>{jar_manifest}
+      </plugin>
+{attached_plugins}      <plugin>
+        <groupId>org.apache.maven.plugins</groupId>
+        <artifactId>maven-surefire-plugin</artifactId>
+        <version>3.2.5</version>
+        <configuration>
+          <redirectTestOutputToFile>${{rsj.capture}}</redirectTestOutputToFile>
+          <failIfNoSpecifiedTests>false</failIfNoSpecifiedTests>
+        </configuration>
+      </plugin>
+{shade_plugin}    </plugins>
+  </build>
+</project>
+"#,
+        group = xml_escape(&project.base_namespace),
+        artifact = xml_escape(&project.name),
+        version = xml_escape(&project.version),
+        release = match &project.java_version {
+            Some(java_version) => format!(
+                "    <maven.compiler.release>{}</maven.compiler.release>\n",
+                xml_escape(java_version)
+            ),
+            None => String::new(),
+        },
+        debug = if config.profile.debug {
+            ""
+        } else {
+            "    <maven.compiler.debug>false</maven.compiler.debug>\n"
+        },
+    ))
+}
@@ -0,0 +1,125 @@
+// `resources/` and `test-resources/` hold files that belong on the classpath next
+// to the classes, such as configuration and `META-INF/services` entries. Unlike
+// sources they keep their paths, relative to the root of the JAR.
+
+use regex::{Captures, Regex};
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+use crate::config::Config;
+use crate::error::RsjError;
+use crate::utils::write_bytes_if_changed;
+
+pub const RESOURCES_PATH: &str = "resources";
+pub const TEST_RESOURCES_PATH: &str = "test-resources";
+
+/// Copy every file below `src_dir` to `dest_dir`, replacing the `${...}`
+/// placeholders of the files `[resources] filter` selects. Returns the written files.
+pub fn copy_resources(
+    config: &Config,
+    src_dir: &Path,
+    dest_dir: &Path,
+) -> Result<Vec<PathBuf>, RsjError> {
+    let filters: Vec<Regex> = config
+        .resources
+        .as_ref()
+        .map(|resources| {
+            resources
+                .filter
+                .iter()
+                .map(|glob| glob_regex(glob))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let mut written = Vec::new();
+    if !src_dir.is_dir() {
+        return Ok(written);
+    }
+    for entry in WalkDir::new(src_dir)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter(|e| e.file_type().is_file())
+    {
+        let relative = entry.path().strip_prefix(src_dir).unwrap();
+        let target = dest_dir.join(relative);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| RsjError::io("Failed to create resource directory", e))?;
+        }
+
+        let mut content = fs::read(entry.path())
+            .map_err(|e| RsjError::io(format!("Failed to read `{}`", entry.path().display()), e))?;
+        let relative = relative.to_string_lossy().replace('\\', "/");
+        if filters.iter().any(|filter| filter.is_match(&relative)) {
+            // Binary files cannot hold placeholders
+            if let Ok(text) = String::from_utf8(content.clone()) {
+                content = filter_placeholders(config, &text).into_bytes();
+            }
+        }
+        write_bytes_if_changed(&target, &content)?;
+        written.push(target);
+    }
+    Ok(written)
+}
+
+/// Whether `glob` is a usable `[resources] filter` pattern.
+pub fn is_valid_glob(glob: &str) -> bool {
+    !glob.is_empty() && !glob.starts_with('/') && !glob.contains('\\')
+}
+
+// Translate a glob into a regex over `/`-separated relative paths. `**/` matches
+// any number of directories, and a pattern without `/` matches file names anywhere.
+fn glob_regex(glob: &str) -> Regex {
+    let glob = if glob.contains('/') {
+        glob.to_string()
+    } else {
+        format!("**/{}", glob)
+    };
+
+    let mut pattern = String::from("^");
+    let mut rest = glob.as_str();
+    while let Some(c) = rest.chars().next() {
+        if let Some(after) = rest.strip_prefix("**/") {
+            pattern.push_str("(?:.*/)?");
+            rest = after;
+            continue;
+        }
+        if let Some(after) = rest.strip_prefix("**") {
+            pattern.push_str(".*");
+            rest = after;
+            continue;
+        }
+        match c {
+            '*' => pattern.push_str("[^/]*"),
+            '?' => pattern.push_str("[^/]"),
+            _ => pattern.push_str(&regex::escape(&c.to_string())),
+        }
+        rest = &rest[c.len_utf8()..];
+    }
+    pattern.push('$');
+    Regex::new(&pattern).unwrap()
+}
+
+// Replace `${project.version}` and the like. Unknown placeholders stay as they are,
+// since resources such as Spring configuration use the same syntax for their own.
+fn filter_placeholders(config: &Config, text: &str) -> String {
+    let placeholder = Regex::new(r"\$\{([A-Za-z0-9_.-]+)\}").unwrap();
+    placeholder
+        .replace_all(text, |captures: &Captures| {
+            let key = &captures[1];
+            let value = match key {
+                "project.name" => Some(config.project.name.clone()),
+                "project.version" => Some(config.project.version.clone()),
+                "project.base_namespace" => Some(config.project.base_namespace.clone()),
+                _ => config
+                    .resources
+                    .as_ref()
+                    .and_then(|resources| resources.properties.get(key).cloned()),
+            };
+            value.unwrap_or_else(|| captures[0].to_string())
+        })
+        .into_owned()
+}
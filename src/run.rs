@@ -1,41 +1,78 @@
-use std::path::Path;
-use std::process::Command;
-
-use crate::build::build_project;
-use crate::config::load_config;
-use crate::utils::{GRADLE_PATH, OUTPUT_PATH, printinfo, separator};
-
-pub fn run_project() -> Result<(), String> {
-    build_project()?;
-
-    let config = load_config()?;
-    let temp_path = Path::new(OUTPUT_PATH).to_path_buf();
-
-    let jar_path = temp_path
-        .join(GRADLE_PATH)
-        .join("build")
-        .join("libs")
-        .join(format!(
-            "{}-{}.jar",
-            config.project.name, config.project.version
-        ));
-
-    if !jar_path.exists() {
-        return Err("Build output JAR not found.".to_string());
+use std::env;
+use std::process::{Command, ExitStatus};
+
+use crate::binaries::{find_binary, qualified_main_class};
+use crate::build::{build_project, runtime_class_path};
+use crate::config::{Config, RunConfig};
+use crate::error::RsjError;
+use crate::jdk;
+use crate::lock::LockMode;
+use crate::utils::{printinfo, separator};
+
+/// Build the project and run one of its binaries with the `[run]` settings of rsj.toml.
+/// `jvm_args` from the command line come after the configured ones, so they win.
+pub fn run_project(
+    config: &Config,
+    lock_mode: &LockMode,
+    bin: Option<&str>,
+    jvm_args: &[String],
+    args: &[String],
+) -> Result<(), RsjError> {
+    let artifacts = build_project(config, lock_mode)?;
+
+    let binary = find_binary(config, bin)?;
+    let class_path = runtime_class_path(config, &artifacts)?;
+    let main_class = qualified_main_class(config, &binary);
+    let class_path = env::join_paths(&class_path)
+        .map_err(|e| RsjError::Config(format!("Invalid dependency path: {}", e)))?;
+
+    let default = RunConfig::default();
+    let run = config.run.as_ref().unwrap_or(&default);
+
+    // The runtime of the JDK the project asks for, which may be newer than its release
+    let java = jdk::program(jdk::project_jdk(config)?.as_ref(), "java");
+    let mut command = Command::new(&java);
+    // The JVM keeps the last `-D` of a property, so the configured ones go first
+    for (key, value) in &run.system_properties {
+        command.arg(format!("-D{}={}", key, value));
+    }
+    command
+        .args(&run.jvm_args)
+        .args(&config.profile.jvm_args)
+        .args(jvm_args)
+        .arg("-cp")
+        .arg(class_path)
+        .arg(&main_class)
+        .args(args)
+        .envs(&run.env);
+    if let Some(working_dir) = &run.working_dir {
+        command.current_dir(config.manifest_dir.join(working_dir));
     }
 
-    printinfo(&format!("Running {}", jar_path.display()));
+    printinfo(&format!("Running `{}` ({})", binary.name, main_class));
     separator();
 
-    let status = Command::new("java")
-        .arg("-jar")
-        .arg(&jar_path)
+    let status = command
         .status()
-        .map_err(|_| "Failed to run the Java application.".to_string())?;
+        .map_err(|e| RsjError::tool_not_found(java, e))?;
 
     if status.success() {
         Ok(())
     } else {
-        Err("Java application exited with an error.".to_string())
+        Err(RsjError::JavaFailed {
+            status: exit_code(&status),
+        })
+    }
+}
+
+// The exit code to forward, using the shell convention of 128 + signal on Unix
+fn exit_code(status: &ExitStatus) -> Option<i32> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        if let Some(signal) = status.signal() {
+            return Some(128 + signal);
+        }
     }
+    status.code()
 }
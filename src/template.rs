@@ -0,0 +1,291 @@
+// Project templates for `rsj init` and `rsj new`: the built-in ones below, or the
+// files of any directory or git repository. Paths and contents may use
+// `{{variable}}` placeholders, see `Variables`.
+
+use regex::{Captures, Regex};
+use std::env;
+use std::fs;
+use std::path::{Component, Path};
+use std::process::{Command, Stdio};
+use walkdir::WalkDir;
+
+use crate::config::MANIFEST_NAME;
+use crate::error::RsjError;
+
+/// A template shipped with rsj.
+struct Builtin {
+    name: &'static str,
+    lib: bool,
+    /// Sections appended to the generated rsj.toml, e.g. dependencies
+    manifest: &'static str,
+    /// Rendered to `src/<main_class>.java` if the project has a main class
+    main: &'static str,
+    files: &'static [(&'static str, &'static str)],
+}
+
+const BUILTINS: &[Builtin] = &[
+    Builtin {
+        name: "cli",
+        lib: false,
+        manifest: "",
+        main: include_str!("../templates/cli/Main.java"),
+        files: &[
+            (
+                "src/classone/ClassOne.java",
+                include_str!("../templates/cli/ClassOne.java"),
+            ),
+            (
+                "src/classtwo/ClassTwo.java",
+                include_str!("../templates/cli/ClassTwo.java"),
+            ),
+        ],
+    },
+    Builtin {
+        name: "lib",
+        lib: true,
+        manifest: "",
+        main: include_str!("../templates/lib/Main.java"),
+        files: &[(
+            "src/Library.java",
+            include_str!("../templates/lib/Library.java"),
+        )],
+    },
+    Builtin {
+        name: "picocli",
+        lib: false,
+        manifest: r#"[dependencies]
+picocli = "info.picocli:picocli:4.7.5"
+
+[build-dependencies]
+picocli-codegen = "info.picocli:picocli-codegen:4.7.5" # Checks the annotations at compile time
+"#,
+        main: include_str!("../templates/picocli/Main.java"),
+        files: &[],
+    },
+    Builtin {
+        name: "javafx",
+        lib: false,
+        manifest: r#"# JavaFX publishes one JAR per platform, this is the one rsj init ran on
+[dependencies]
+javafx-base = { group = "org.openjfx", version = "21.0.1", classifier = "{{javafx_platform}}" }
+javafx-graphics = { group = "org.openjfx", version = "21.0.1", classifier = "{{javafx_platform}}" }
+javafx-controls = { group = "org.openjfx", version = "21.0.1", classifier = "{{javafx_platform}}" }
+"#,
+        main: include_str!("../templates/javafx/Main.java"),
+        files: &[(
+            "src/ui/App.java",
+            include_str!("../templates/javafx/App.java"),
+        )],
+    },
+    Builtin {
+        name: "http",
+        lib: false,
+        manifest: "",
+        main: include_str!("../templates/http/Main.java"),
+        files: &[(
+            "src/handlers/HelloHandler.java",
+            include_str!("../templates/http/HelloHandler.java"),
+        )],
+    },
+];
+
+/// Values substituted for the `{{...}}` placeholders of a template.
+pub struct Variables {
+    pub name: String,
+    pub version: String,
+    pub namespace: String,
+    pub main_class: Option<String>,
+    pub java_version: Option<String>,
+}
+
+impl Variables {
+    fn get(&self, key: &str) -> Option<String> {
+        let main_class = self.main_class.as_deref().unwrap_or("Main");
+        let (subpackage, class_name) = match main_class.rsplit_once('.') {
+            Some((subpackage, class_name)) => (Some(subpackage), class_name),
+            None => (None, main_class),
+        };
+        let value = match key {
+            "name" => self.name.clone(),
+            "version" => self.version.clone(),
+            "namespace" => self.namespace.clone(),
+            "namespace_path" => self.namespace.replace('.', "/"),
+            "main_class" => main_class.to_string(),
+            "main_class_name" => class_name.to_string(),
+            "main_class_path" => main_class.replace('.', "/"),
+            // Package of the main class, which may sit in a subpackage
+            "main_package" => match subpackage {
+                Some(subpackage) => format!("{}.{}", self.namespace, subpackage),
+                None => self.namespace.clone(),
+            },
+            "java_version" => self.java_version.clone().unwrap_or_default(),
+            "javafx_platform" => javafx_platform().to_string(),
+            _ => return None,
+        };
+        Some(value)
+    }
+
+    /// Replace every known placeholder in `text`. Unknown ones stay as they are, so
+    /// Java code such as `new int[][] {{1}}` survives.
+    pub fn render(&self, text: &str) -> String {
+        let placeholder = Regex::new(r"\{\{\s*([A-Za-z_]+)\s*\}\}").unwrap();
+        placeholder
+            .replace_all(text, |captures: &Captures| {
+                self.get(&captures[1])
+                    .unwrap_or_else(|| captures[0].to_string())
+            })
+            .into_owned()
+    }
+}
+
+// The classifier of the JavaFX JARs for this machine
+fn javafx_platform() -> &'static str {
+    match (env::consts::OS, env::consts::ARCH) {
+        ("macos", "aarch64") => "mac-aarch64",
+        ("macos", _) => "mac",
+        ("windows", _) => "win",
+        (_, "aarch64") => "linux-aarch64",
+        _ => "linux",
+    }
+}
+
+/// A template ready to render: its files with unrendered paths and contents.
+pub struct Template {
+    pub lib: bool,
+    manifest: String,
+    main: Option<&'static str>,
+    files: Vec<(String, Vec<u8>)>,
+}
+
+impl Template {
+    /// Load the template `spec` names: a built-in template, a directory, or a git
+    /// repository URL. Without one, `lib` or `cli` depending on `lib`.
+    pub fn load(spec: Option<&str>, lib: bool) -> Result<Template, RsjError> {
+        let spec = spec.unwrap_or(if lib { "lib" } else { "cli" });
+        if let Some(builtin) = BUILTINS.iter().find(|builtin| builtin.name == spec) {
+            return Ok(Template {
+                lib: builtin.lib,
+                manifest: builtin.manifest.to_string(),
+                main: Some(builtin.main),
+                files: builtin
+                    .files
+                    .iter()
+                    .map(|(path, content)| (path.to_string(), content.as_bytes().to_vec()))
+                    .collect(),
+            });
+        }
+
+        let files = if Path::new(spec).is_dir() && !spec.ends_with(".git") {
+            read_template_dir(Path::new(spec))?
+        } else if is_git_url(spec) {
+            clone_template(spec)?
+        } else {
+            let names: Vec<&str> = BUILTINS.iter().map(|builtin| builtin.name).collect();
+            return Err(RsjError::Config(format!(
+                "Error: Unknown template `{}`. Use one of {}, a directory or a git repository URL.",
+                spec,
+                names.join(", ")
+            )));
+        };
+        Ok(Template {
+            lib,
+            manifest: String::new(),
+            main: None,
+            files,
+        })
+    }
+
+    /// Whether the template brings its own rsj.toml instead of a generated one.
+    pub fn has_manifest(&self) -> bool {
+        self.files.iter().any(|(path, _)| path == MANIFEST_NAME)
+    }
+
+    /// Sections the generated rsj.toml should end with.
+    pub fn manifest_sections(&self, variables: &Variables) -> String {
+        variables.render(&self.manifest)
+    }
+
+    /// The rendered files, relative to the project directory.
+    pub fn render(&self, variables: &Variables) -> Result<Vec<(String, Vec<u8>)>, RsjError> {
+        let mut rendered = Vec::new();
+        if let (Some(main), Some(_)) = (self.main, &variables.main_class) {
+            rendered.push((
+                variables.render("src/{{main_class_path}}.java"),
+                variables.render(main).into_bytes(),
+            ));
+        }
+
+        for (path, content) in &self.files {
+            let path = variables.render(path);
+            // Rendered names must stay inside the project
+            let escapes = Path::new(&path)
+                .components()
+                .any(|component| !matches!(component, Component::Normal(_)));
+            if escapes {
+                return Err(RsjError::Config(format!(
+                    "Error: Template file `{}` is outside the project directory.",
+                    path
+                )));
+            }
+            // Binary files are copied as they are
+            let content = match std::str::from_utf8(content) {
+                Ok(text) => variables.render(text).into_bytes(),
+                Err(_) => content.clone(),
+            };
+            rendered.push((path, content));
+        }
+        Ok(rendered)
+    }
+}
+
+fn is_git_url(spec: &str) -> bool {
+    spec.contains("://") || spec.starts_with("git@") || spec.ends_with(".git")
+}
+
+// Every file below `dir` but the git metadata, with `/`-separated relative paths
+fn read_template_dir(dir: &Path) -> Result<Vec<(String, Vec<u8>)>, RsjError> {
+    let mut files = Vec::new();
+    for entry in WalkDir::new(dir)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|entry| entry.file_name() != ".git")
+    {
+        let entry = entry.map_err(|e| {
+            RsjError::io(
+                format!("Failed to read template `{}`", dir.display()),
+                e.into(),
+            )
+        })?;
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let relative = entry.path().strip_prefix(dir).unwrap();
+        let content = fs::read(entry.path())
+            .map_err(|e| RsjError::io(format!("Failed to read `{}`", entry.path().display()), e))?;
+        files.push((relative.to_string_lossy().replace('\\', "/"), content));
+    }
+    Ok(files)
+}
+
+// Shallow-clone a template repository into a scratch directory and read it
+fn clone_template(url: &str) -> Result<Vec<(String, Vec<u8>)>, RsjError> {
+    let scratch = tempfile::tempdir()
+        .map_err(|e| RsjError::io("Failed to create a directory for the template", e))?;
+    let checkout = scratch.path().join("template");
+
+    let status = Command::new("git")
+        // `--` so a spec starting with `-` is not taken for an option
+        .args(["clone", "--quiet", "--depth", "1", "--", url])
+        .arg(&checkout)
+        .stdout(Stdio::null())
+        .status()
+        .map_err(|e| RsjError::tool_not_found("git", e))?;
+    if !status.success() {
+        return Err(RsjError::Project(format!(
+            "Error: Failed to clone template `{}`.",
+            url
+        )));
+    }
+
+    read_template_dir(&checkout)
+}
@@ -0,0 +1,222 @@
+use colored::Colorize;
+use regex::Regex;
+use std::fs;
+use std::path::Path;
+use std::sync::LazyLock;
+
+use crate::backend::{backend_for, unsupported_tests};
+use crate::build::{build_dir, prepare_project};
+use crate::config::Config;
+use crate::error::RsjError;
+use crate::lock::LockMode;
+use crate::utils::separator;
+
+// JUnit XML is regular enough for a few patterns, which saves an XML parser
+static TESTCASE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"(?s)<testcase\s([^>]*?)(?:/>|>(.*?)</testcase>)"#).unwrap());
+static FAILURE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?s)<(?:failure|error)\s([^>]*?)(?:/>|>(.*?)</(?:failure|error)>)"#).unwrap()
+});
+static ATTRIBUTE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"([\w:.-]+)="([^"]*)""#).unwrap());
+static ENTITY: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"&(#x[0-9a-fA-F]+|#[0-9]+|lt|gt|amp|quot|apos);").unwrap());
+
+enum Outcome {
+    Passed,
+    Failed(String),
+    Ignored,
+}
+
+struct TestCase {
+    name: String,
+    time: f64,
+    outcome: Outcome,
+}
+
+pub fn test_project(
+    config: &Config,
+    filter: Option<String>,
+    nocapture: bool,
+    lock_mode: &LockMode,
+) -> Result<(), RsjError> {
+    let backend = backend_for(config);
+    let Some(results_dir) = backend.test_results_dir(&build_dir(config)) else {
+        return Err(unsupported_tests(backend.name()));
+    };
+    let (backend, temp_path) = prepare_project(config, lock_mode)?;
+
+    // Remove old reports so only this run is summarized
+    if results_dir.exists() {
+        fs::remove_dir_all(&results_dir)
+            .map_err(|e| RsjError::io("Failed to remove old test results", e))?;
+    }
+
+    let tool_result = backend.run_tests(config, &temp_path, filter.as_deref(), nocapture);
+
+    let tests = read_test_results(&results_dir)?;
+    if tests.is_empty() {
+        // Nothing ran, so a Gradle failure means the tests did not compile
+        tool_result?;
+        separator();
+        print_summary(&tests);
+        return Ok(());
+    }
+
+    separator();
+
+    let failed = print_summary(&tests);
+    if failed > 0 {
+        return Err(RsjError::TestsFailed { failed });
+    }
+
+    tool_result
+}
+
+// Parse every `TEST-*.xml` JUnit report Gradle wrote
+fn read_test_results(results_dir: &Path) -> Result<Vec<TestCase>, RsjError> {
+    let mut tests = Vec::new();
+    if !results_dir.exists() {
+        return Ok(tests);
+    }
+
+    let mut reports: Vec<_> = fs::read_dir(results_dir)
+        .map_err(|e| RsjError::io("Failed to read test results", e))?
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().and_then(|ext| ext.to_str()) == Some("xml"))
+        .collect();
+    reports.sort();
+
+    for report in reports {
+        let content = fs::read_to_string(&report)
+            .map_err(|e| RsjError::io(format!("Failed to read `{}`", report.display()), e))?;
+        tests.extend(parse_junit_report(&content));
+    }
+
+    Ok(tests)
+}
+
+fn parse_junit_report(content: &str) -> Vec<TestCase> {
+    TESTCASE
+        .captures_iter(content)
+        .map(|caps| {
+            let attrs = &caps[1];
+            let body = caps.get(2).map_or("", |m| m.as_str());
+            let name = attribute(attrs, "name").unwrap_or_default();
+            let class_name = attribute(attrs, "classname").unwrap_or_default();
+
+            let outcome = if let Some(failure) = FAILURE.captures(body) {
+                let message = failure
+                    .get(2)
+                    .map(|m| unescape_xml(m.as_str().trim()))
+                    .filter(|text| !text.is_empty())
+                    .or_else(|| attribute(&failure[1], "message"))
+                    .unwrap_or_default();
+                Outcome::Failed(message)
+            } else if body.contains("<skipped") {
+                Outcome::Ignored
+            } else {
+                Outcome::Passed
+            };
+
+            TestCase {
+                name: format!("{}::{}", class_name, name.trim_end_matches("()")),
+                time: attribute(attrs, "time")
+                    .and_then(|t| t.parse().ok())
+                    .unwrap_or(0.0),
+                outcome,
+            }
+        })
+        .collect()
+}
+
+fn attribute(attrs: &str, name: &str) -> Option<String> {
+    ATTRIBUTE
+        .captures_iter(attrs)
+        .find(|caps| &caps[1] == name)
+        .map(|caps| unescape_xml(&caps[2]))
+}
+
+fn unescape_xml(text: &str) -> String {
+    ENTITY
+        .replace_all(text, |caps: &regex::Captures| {
+            let code = &caps[1];
+            let decoded = match code {
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "amp" => Some('&'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                _ if code.starts_with("#x") => u32::from_str_radix(&code[2..], 16)
+                    .ok()
+                    .and_then(char::from_u32),
+                _ => code[1..].parse().ok().and_then(char::from_u32),
+            };
+            decoded.map_or_else(|| caps[0].to_string(), String::from)
+        })
+        .into_owned()
+}
+
+// Print a cargo-like report and return the number of failed tests
+fn print_summary(tests: &[TestCase]) -> usize {
+    println!(
+        "running {} test{}",
+        tests.len(),
+        if tests.len() == 1 { "" } else { "s" }
+    );
+
+    for test in tests {
+        let status = match test.outcome {
+            Outcome::Passed => "ok".green(),
+            Outcome::Failed(_) => "FAILED".red(),
+            Outcome::Ignored => "ignored".yellow(),
+        };
+        println!("test {} ... {}", test.name, status);
+    }
+
+    let failures: Vec<_> = tests
+        .iter()
+        .filter_map(|test| match &test.outcome {
+            Outcome::Failed(message) => Some((test.name.as_str(), message.as_str())),
+            _ => None,
+        })
+        .collect();
+
+    if !failures.is_empty() {
+        println!("\nfailures:\n");
+        for (name, message) in &failures {
+            println!("---- {} ----\n{}\n", name, message);
+        }
+        println!("failures:");
+        for (name, _) in &failures {
+            println!("    {}", name);
+        }
+    }
+
+    let passed = tests
+        .iter()
+        .filter(|test| matches!(test.outcome, Outcome::Passed))
+        .count();
+    let ignored = tests
+        .iter()
+        .filter(|test| matches!(test.outcome, Outcome::Ignored))
+        .count();
+    let time: f64 = tests.iter().map(|test| test.time).sum();
+    let result = if failures.is_empty() {
+        "ok".green()
+    } else {
+        "FAILED".red()
+    };
+
+    println!(
+        "\ntest result: {}. {} passed; {} failed; {} ignored; finished in {:.2}s",
+        result,
+        passed,
+        failures.len(),
+        ignored,
+        time
+    );
+
+    failures.len()
+}
@@ -1,10 +1,17 @@
 use colored::Colorize;
+use std::collections::HashSet;
+use std::env;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use walkdir::WalkDir;
 
+use crate::error::RsjError;
+use crate::java::{find_package_declaration, rewrite_package};
+
 pub const GRADLE_PATH: &str = "gradle";
 pub const OUTPUT_PATH: &str = "rsj_build";
+pub const FINGERPRINT_PATH: &str = "fingerprint";
+pub const BIN_PATH: &str = "bin";
 
 pub fn printerr(msg: &str) {
     println!("{}{}", "[ERROR] ".red().bold(), msg);
@@ -21,16 +28,24 @@ pub fn separator() {
     );
 }
 
-pub fn copy_src_files(src_dir: &str, dest_dir: &Path, base_namespace: &str) -> Result<(), String> {
+pub fn copy_src_files(
+    src_dir: &str,
+    dest_dir: &Path,
+    base_namespace: &str,
+    include: impl Fn(&Path) -> bool,
+) -> Result<Vec<PathBuf>, RsjError> {
+    let mut written = Vec::new();
+
     for entry in WalkDir::new(src_dir)
         .into_iter()
         .filter_map(Result::ok)
         .filter(|e| e.path().extension().and_then(|ext| ext.to_str()) == Some("java"))
+        .filter(|e| include(e.path()))
     {
         let path = entry.path();
         let relative_path = path
             .strip_prefix(src_dir)
-            .map_err(|_| "Failed to determine relative path.".to_string())?;
+            .map_err(|_| RsjError::Source("Failed to determine relative path.".to_string()))?;
         let parent = relative_path.parent().unwrap_or_else(|| Path::new(""));
         let relative_package = parent
             .to_string_lossy()
@@ -43,21 +58,120 @@ pub fn copy_src_files(src_dir: &str, dest_dir: &Path, base_namespace: &str) -> R
 
         let target_dir = dest_dir.join(parent);
         fs::create_dir_all(&target_dir)
-            .map_err(|e| format!("Failed to create package directory: {}", e))?;
+            .map_err(|e| RsjError::io("Failed to create package directory", e))?;
 
         let content =
-            fs::read_to_string(path).map_err(|e| format!("Failed to read Java file: {}", e))?;
-        let new_content = if content.contains("package ") {
-            let parts: Vec<&str> = content.splitn(2, ';').collect();
-            let rest = parts.get(1).unwrap_or(&"");
-            format!("package {};\n{}", package, rest)
-        } else {
-            format!("package {};\n{}", package, content)
-        };
+            fs::read_to_string(path).map_err(|e| RsjError::io("Failed to read Java file", e))?;
+        check_declared_package(path, &content, &package, &relative_package)?;
+        let new_content = rewrite_package(&content, &package).map_err(|e| {
+            RsjError::Source(format!("Failed to parse `{}`: {}", path.display(), e))
+        })?;
 
         let target_file = target_dir.join(path.file_name().unwrap());
-        fs::write(&target_file, new_content)
-            .map_err(|e| format!("Failed to write Java file: {}", e))?;
+        write_if_changed(&target_file, &new_content)?;
+        written.push(target_file);
+    }
+    Ok(written)
+}
+
+// The user's home directory, for caches such as `~/.m2` and `~/.gradle`
+pub fn home_dir() -> Option<PathBuf> {
+    env::var_os("HOME")
+        .or_else(|| env::var_os("USERPROFILE"))
+        .map(PathBuf::from)
+}
+
+// Look up an executable on PATH like the shell would
+pub fn find_program(name: &str) -> Option<PathBuf> {
+    let path = env::var_os("PATH")?;
+    env::split_paths(&path)
+        .map(|dir| dir.join(name))
+        .find(|candidate| candidate.is_file())
+}
+
+// Write a file only if its content differs, so Gradle's up-to-date checks keep working
+pub fn write_if_changed(path: &Path, content: &str) -> Result<(), RsjError> {
+    write_bytes_if_changed(path, content.as_bytes())
+}
+
+// `write_if_changed` for content that may not be text
+pub fn write_bytes_if_changed(path: &Path, content: &[u8]) -> Result<(), RsjError> {
+    if fs::read(path).is_ok_and(|existing| existing == content) {
+        return Ok(());
     }
+    fs::write(path, content)
+        .map_err(|e| RsjError::io(format!("Failed to write `{}`", path.display()), e))
+}
+
+// A relative path with `/` separators on every platform, e.g. `lib/gson.jar`
+pub fn slash_path(path: &Path) -> String {
+    let parts: Vec<String> = path
+        .components()
+        .map(|part| part.as_os_str().to_string_lossy().to_string())
+        .collect();
+    parts.join("/")
+}
+
+// Delete files below `dir` that are not in `keep`, and any directories left empty
+pub fn remove_stale_files(dir: &Path, keep: &HashSet<PathBuf>) -> Result<(), RsjError> {
+    if !dir.exists() {
+        return Ok(());
+    }
+
+    // Children come before their parents, so emptied directories can go too
+    for entry in WalkDir::new(dir)
+        .min_depth(1)
+        .contents_first(true)
+        .into_iter()
+        .filter_map(Result::ok)
+    {
+        let path = entry.path();
+        if entry.file_type().is_dir() {
+            if fs::read_dir(path).is_ok_and(|mut entries| entries.next().is_none()) {
+                fs::remove_dir(path).map_err(|e| {
+                    RsjError::io(format!("Failed to remove `{}`", path.display()), e)
+                })?;
+            }
+        } else if !keep.contains(path) {
+            fs::remove_file(path)
+                .map_err(|e| RsjError::io(format!("Failed to remove `{}`", path.display()), e))?;
+        }
+    }
+
     Ok(())
 }
+
+// Test classes living next to the code they test, e.g. `src/util/UtilTest.java`
+pub fn is_inline_test(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name.ends_with("Test.java") || name.ends_with("Tests.java"))
+}
+
+// A file may omit its package, or declare either the full package implied by its
+// directory or just the directory-relative part of it. Anything else is an error.
+fn check_declared_package(
+    path: &Path,
+    content: &str,
+    package: &str,
+    relative_package: &str,
+) -> Result<(), RsjError> {
+    let declaration = find_package_declaration(content)
+        .map_err(|e| RsjError::Source(format!("Failed to parse `{}`: {}", path.display(), e)))?;
+
+    match declaration {
+        Some(declared)
+            if declared.name != package
+                && (relative_package.is_empty() || declared.name != relative_package) =>
+        {
+            Err(RsjError::Source(format!(
+                "`{}:{}` declares package `{}`, but its directory implies `{}`.",
+                path.display(),
+                declared.line,
+                declared.name,
+                package
+            )))
+        }
+        _ => Ok(()),
+    }
+}
@@ -0,0 +1,559 @@
+// Checks rsj.toml beyond what serde enforces, so a typo fails right away with the
+// offending line instead of minutes later inside Gradle. Problems are located in
+// the manifest through a second, span-preserving parse with `toml_edit`.
+
+use regex::Regex;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use toml_edit::{ImDocument, Item};
+
+use crate::build::project_root;
+use crate::build_info;
+use crate::config::{Config, DEV_PROFILE, Dependency, DependencyKind, RELEASE_PROFILE};
+use crate::diagnostics::{Diagnostic, render_diagnostic};
+use crate::java;
+use crate::resources;
+
+// Keys of a detailed dependency. serde cannot report unknown keys inside one since
+// it tries each form of the untagged `Dependency` in turn.
+const DEPENDENCY_KEYS: &[&str] = &[
+    "group",
+    "artifact",
+    "version",
+    "classifier",
+    "scope",
+    "exclude",
+    "optional",
+];
+
+/// Collects errors and warnings about rsj.toml and renders them against its lines.
+pub struct Validator {
+    path: PathBuf,
+    content: String,
+    document: Option<ImDocument<String>>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Validator {
+    pub fn new(path: &Path, content: &str) -> Self {
+        Validator {
+            path: path.to_path_buf(),
+            content: content.to_string(),
+            document: ImDocument::parse(content.to_string()).ok(),
+            diagnostics: Vec::new(),
+        }
+    }
+
+    /// An error about the value at `keys`, e.g. `["project", "version"]`.
+    pub fn error(&mut self, keys: &[&str], message: String) {
+        let span = self.find(keys, false);
+        self.push(span, true, message);
+    }
+
+    /// An error at a byte range of the manifest, as reported by the TOML parser.
+    pub fn error_at(&mut self, span: Option<Range<usize>>, message: &str) {
+        self.push(span, true, message.to_string());
+    }
+
+    /// A warning about a key rsj does not use, which is most likely a typo.
+    pub fn unused_key(&mut self, keys: &[String]) {
+        let keys: Vec<&str> = keys.iter().map(String::as_str).collect();
+        let span = self.find(&keys, true);
+        self.push(
+            span,
+            false,
+            format!("unused manifest key: {}", keys.join(".")),
+        );
+    }
+
+    /// Print every diagnostic, returning the number of errors among them.
+    pub fn report(self) -> usize {
+        for diagnostic in &self.diagnostics {
+            println!("{}", render_diagnostic(diagnostic));
+        }
+        self.diagnostics.iter().filter(|d| d.is_error).count()
+    }
+
+    fn push(&mut self, span: Option<Range<usize>>, is_error: bool, message: String) {
+        // Without a location the diagnostic still names the manifest
+        let (line, column, width) = match span {
+            Some(span) => self.position(span),
+            None => (0, None, 1),
+        };
+        self.diagnostics.push(Diagnostic {
+            path: self.path.clone(),
+            line,
+            column,
+            width,
+            is_error,
+            message,
+            notes: Vec::new(),
+        });
+    }
+
+    // 1-based line and column of a byte range, and its width up to the end of the line
+    fn position(&self, span: Range<usize>) -> (usize, Option<usize>, usize) {
+        let start = span.start.min(self.content.len());
+        let before = &self.content[..start];
+        let line = before.matches('\n').count() + 1;
+        let line_start = before.rfind('\n').map(|i| i + 1).unwrap_or(0);
+        let column = before[line_start..].chars().count() + 1;
+        let width = self.content[start..span.end.clamp(start, self.content.len())]
+            .lines()
+            .next()
+            .map(|text| text.chars().count())
+            .unwrap_or(0);
+        (line, Some(column), width)
+    }
+
+    // Span of the value at `keys`, or of the last key itself when `key` is set
+    fn find(&self, keys: &[&str], key: bool) -> Option<Range<usize>> {
+        let document = self.document.as_ref()?;
+        let (last, parents) = keys.split_last()?;
+
+        let mut item = document.as_item();
+        for part in parents {
+            item = match item {
+                Item::Value(toml_edit::Value::Array(array)) => {
+                    return array.get(part.parse().ok()?)?.span();
+                }
+                Item::ArrayOfTables(tables) => {
+                    let table = tables.get(part.parse().ok()?)?;
+                    let (found_key, found) = table.get_key_value(last)?;
+                    return if key { found_key.span() } else { found.span() };
+                }
+                _ => item.as_table_like()?.get(part)?,
+            };
+        }
+        if let Some(array) = item.as_array() {
+            return array.get(last.parse().ok()?)?.span();
+        }
+        let (found_key, found) = item.as_table_like()?.get_key_value(last)?;
+        if key { found_key.span() } else { found.span() }
+    }
+
+    // Keys of the detailed dependency at `keys`, if it is a table
+    fn table_keys(&self, keys: &[&str]) -> Vec<String> {
+        let Some(document) = &self.document else {
+            return Vec::new();
+        };
+        let mut item = document.as_item();
+        for part in keys {
+            match item.as_table_like().and_then(|table| table.get(part)) {
+                Some(found) => item = found,
+                None => return Vec::new(),
+            }
+        }
+        match item.as_table_like() {
+            Some(table) => table.iter().map(|(key, _)| key.to_string()).collect(),
+            None => Vec::new(),
+        }
+    }
+}
+
+/// The keys leading to a value `serde_ignored` reports as unused.
+pub fn key_path(path: &serde_ignored::Path) -> Vec<String> {
+    let mut keys = match path {
+        serde_ignored::Path::Root => return Vec::new(),
+        serde_ignored::Path::Seq { parent, .. }
+        | serde_ignored::Path::Map { parent, .. }
+        | serde_ignored::Path::Some { parent }
+        | serde_ignored::Path::NewtypeStruct { parent }
+        | serde_ignored::Path::NewtypeVariant { parent } => key_path(parent),
+    };
+    match path {
+        serde_ignored::Path::Seq { index, .. } => keys.push(index.to_string()),
+        serde_ignored::Path::Map { key, .. } => keys.push(key.clone()),
+        _ => {}
+    }
+    keys
+}
+
+/// Check a project name. Gradle refuses these characters in project names, and
+/// the name ends up in file names.
+pub fn check_project_name(name: &str) -> Result<(), String> {
+    if name.trim().is_empty() {
+        Err("`name` cannot be empty".to_string())
+    } else if name.contains(|c: char| "/\\:<>\"?*|".contains(c) || c.is_control()) {
+        Err(format!(
+            "invalid `name` `{}`, it cannot contain any of / \\ : < > \" ? * |",
+            name
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+/// Check a Java release number such as `17`, as javac's `--release` takes it.
+pub fn check_java_version(version: &str) -> Result<(), String> {
+    match version.parse::<u32>() {
+        Ok(release) if release >= 8 && !version.starts_with('0') => Ok(()),
+        _ => Err(format!(
+            "invalid `java_version` `{}`, expected a Java release such as `17`",
+            version
+        )),
+    }
+}
+
+/// Check every value of rsj.toml that must follow a particular syntax.
+pub fn validate(config: &Config, validator: &mut Validator) {
+    let project = &config.project;
+
+    if let Err(message) = check_project_name(&project.name) {
+        validator.error(&["project", "name"], message);
+    }
+
+    let version = Regex::new(r"^\d+(\.\d+){0,2}(-[0-9A-Za-z.-]+)?(\+[0-9A-Za-z.-]+)?$").unwrap();
+    if !version.is_match(&project.version) {
+        validator.error(
+            &["project", "version"],
+            format!(
+                "invalid `version` `{}`, expected a semantic version such as `1.0.0` or `1.0.0-SNAPSHOT`",
+                project.version
+            ),
+        );
+    }
+
+    if !java::is_qualified_name(&project.base_namespace) {
+        validator.error(
+            &["project", "base_namespace"],
+            format!(
+                "invalid `base_namespace` `{}`, expected a Java package name such as `com.example`",
+                project.base_namespace
+            ),
+        );
+    }
+
+    match &project.main_class {
+        Some(main_class) => {
+            validate_main_class(config, validator, &["project", "main_class"], main_class)
+        }
+        None if !config.is_library() => validator.error(
+            &["project"],
+            "missing `main_class`, which applications require; add `[lib]` for a library"
+                .to_string(),
+        ),
+        None => {}
+    }
+    validate_binaries(config, validator);
+    validate_profiles(config, validator);
+
+    if let Some(java_version) = &project.java_version
+        && let Err(message) = check_java_version(java_version)
+    {
+        validator.error(&["project", "java_version"], message);
+    }
+    match &project.vendor {
+        Some(vendor) if vendor.trim().is_empty() => validator.error(
+            &["project", "vendor"],
+            "`vendor` is empty, expected a JDK vendor such as `adoptium`".to_string(),
+        ),
+        Some(_) if project.java_version.is_none() => validator.error(
+            &["project", "vendor"],
+            "`vendor` requires `java_version`, the JDK it selects".to_string(),
+        ),
+        _ => {}
+    }
+
+    // Both versions end up in URLs
+    let gradle_version = Regex::new(r"^\d+(\.\d+){1,2}(-(rc|milestone)-\d+)?$").unwrap();
+    if let Some(value) = &project.gradle_version
+        && !gradle_version.is_match(value)
+    {
+        validator.error(
+            &["project", "gradle_version"],
+            format!(
+                "invalid `gradle_version` `{}`, expected a Gradle release such as `8.4`",
+                value
+            ),
+        );
+    }
+    if let Some(value) = &project.gradle_distribution_url
+        && !value.contains("://")
+        && !config.manifest_dir.join(value).is_file()
+    {
+        validator.error(
+            &["project", "gradle_distribution_url"],
+            format!("Gradle distribution `{}` does not exist", value),
+        );
+    }
+    if let Some(value) = &project.gradle_distribution_sha256
+        && !(value.len() == 64 && value.chars().all(|c| c.is_ascii_hexdigit()))
+    {
+        validator.error(
+            &["project", "gradle_distribution_sha256"],
+            format!(
+                "invalid `gradle_distribution_sha256` `{}`, expected 64 hexadecimal digits",
+                value
+            ),
+        );
+    }
+    if let Some(value) = &project.shadow_plugin_version
+        && !version.is_match(value)
+    {
+        validator.error(
+            &["project", "shadow_plugin_version"],
+            format!(
+                "invalid `shadow_plugin_version` `{}`, expected a version such as `7.1.2`",
+                value
+            ),
+        );
+    }
+
+    if let Some(resources) = &config.resources {
+        for (index, glob) in resources.filter.iter().enumerate() {
+            if !resources::is_valid_glob(glob) {
+                validator.error(
+                    &["resources", "filter", &index.to_string()],
+                    format!(
+                        "invalid `filter` `{}`, expected a relative glob such as `**/*.properties`",
+                        glob
+                    ),
+                );
+            }
+        }
+    }
+
+    if let Some(build_info) = &config.build_info {
+        let class = build_info::class_name(build_info);
+        let class_file = format!("{}.java", class.replace('.', "/"));
+        if !java::is_qualified_name(class) {
+            validator.error(
+                &["build_info", "class"],
+                format!(
+                    "invalid `class` `{}`, expected a Java class name such as `BuildInfo`",
+                    class
+                ),
+            );
+        } else if project_root(config).join("src").join(&class_file).exists() {
+            // The generated class would replace it
+            validator.error(
+                &["build_info", "class"],
+                format!(
+                    "`class` `{}` clashes with `src/{}`, choose another name",
+                    class, class_file
+                ),
+            );
+        }
+    }
+
+    if let Some(working_dir) = config.run.as_ref().and_then(|run| run.working_dir.as_ref())
+        && !config.manifest_dir.join(working_dir).is_dir()
+    {
+        validator.error(
+            &["run", "working_dir"],
+            format!("`working_dir` `{}` is not a directory", working_dir),
+        );
+    }
+
+    for kind in DependencyKind::ALL {
+        for (name, dependency) in config.dependency_table(kind) {
+            validate_dependency(validator, kind.table_name(), name, dependency);
+        }
+    }
+}
+
+// `main_class` must name a class in `src/`
+fn validate_main_class(
+    config: &Config,
+    validator: &mut Validator,
+    keys: &[&str],
+    main_class: &str,
+) {
+    if !java::is_qualified_name(main_class) {
+        validator.error(
+            keys,
+            format!(
+                "invalid `main_class` `{}`, expected a Java class name such as `Main`",
+                main_class
+            ),
+        );
+        return;
+    }
+
+    // A missing `src/` is reported by the build itself
+    let src_dir = project_root(config).join("src");
+    let main_file = format!("{}.java", main_class.replace('.', "/"));
+    if src_dir.is_dir() && !src_dir.join(&main_file).is_file() {
+        validator.error(
+            keys,
+            format!(
+                "`main_class` `{}` was not found, expected `src/{}`",
+                main_class, main_file
+            ),
+        );
+    }
+}
+
+// Custom profiles must inherit, through a chain that ends at `dev` or `release`
+fn validate_profiles(config: &Config, validator: &mut Validator) {
+    let name_pattern = Regex::new(r"^[A-Za-z0-9_-]+$").unwrap();
+    let lint_pattern = Regex::new(r"^-?[a-z][a-z-]*$").unwrap();
+    let builtin = |name: &str| name == DEV_PROFILE || name == RELEASE_PROFILE;
+
+    for (name, settings) in &config.profiles {
+        if !name_pattern.is_match(name) {
+            validator.error(
+                &["profile", name],
+                format!(
+                    "invalid profile name `{}`, expected letters, digits, `_` or `-`",
+                    name
+                ),
+            );
+        }
+        for (index, key) in settings.lint.iter().flatten().enumerate() {
+            if !lint_pattern.is_match(key) {
+                validator.error(
+                    &["profile", name, "lint", &index.to_string()],
+                    format!(
+                        "invalid `lint` `{}`, expected a javac lint key such as `all` or `-serial`",
+                        key
+                    ),
+                );
+            }
+        }
+
+        match &settings.inherits {
+            Some(_) if builtin(name) => validator.error(
+                &["profile", name, "inherits"],
+                format!("the built-in profile `{}` cannot inherit", name),
+            ),
+            None if !builtin(name) => validator.error(
+                &["profile", name],
+                format!(
+                    "profile `{}` needs `inherits`, e.g. `inherits = \"release\"`",
+                    name
+                ),
+            ),
+            Some(parent) if !builtin(parent) && !config.profiles.contains_key(parent) => validator
+                .error(
+                    &["profile", name, "inherits"],
+                    format!("`inherits` names the unknown profile `{}`", parent),
+                ),
+            Some(_) => {
+                // Follow the chain, which must not come back around
+                let mut seen = vec![name.as_str()];
+                let mut current = name.as_str();
+                while let Some(parent) = config
+                    .profiles
+                    .get(current)
+                    .and_then(|settings| settings.inherits.as_deref())
+                    .filter(|parent| !builtin(parent))
+                {
+                    if seen.contains(&parent) {
+                        validator.error(
+                            &["profile", name, "inherits"],
+                            format!(
+                                "the `inherits` chain of `{}` loops back at `{}`",
+                                name, parent
+                            ),
+                        );
+                        break;
+                    }
+                    seen.push(parent);
+                    current = parent;
+                }
+            }
+            None => {}
+        }
+    }
+}
+
+// Binary names become start script names, so they must be unique and file-safe
+fn validate_binaries(config: &Config, validator: &mut Validator) {
+    let name = Regex::new(r"^[A-Za-z0-9_.-]+$").unwrap();
+    let mut seen: Vec<String> = project_binary_name(config).into_iter().collect();
+    for (index, binary) in config.bin.iter().enumerate() {
+        let index = index.to_string();
+        let keys = ["bin", index.as_str(), "name"];
+        if !name.is_match(&binary.name) {
+            validator.error(
+                &keys,
+                format!(
+                    "invalid binary name `{}`, expected letters, digits, `_`, `.` or `-`",
+                    binary.name
+                ),
+            );
+        } else if seen.contains(&binary.name) {
+            validator.error(
+                &keys,
+                format!("binary name `{}` is used more than once", binary.name),
+            );
+        }
+        seen.push(binary.name.clone());
+
+        validate_main_class(
+            config,
+            validator,
+            &["bin", index.as_str(), "main_class"],
+            &binary.main_class,
+        );
+    }
+}
+
+// The project's own binary, named after the project
+fn project_binary_name(config: &Config) -> Option<String> {
+    config
+        .project
+        .main_class
+        .as_ref()
+        .map(|_| config.project.name.clone())
+}
+
+// Maven coordinates may not contain separators, so the notation stays unambiguous
+fn validate_dependency(
+    validator: &mut Validator,
+    table: &str,
+    name: &str,
+    dependency: &Dependency,
+) {
+    let keys = [table, name];
+    if let Dependency::Detailed(_) = dependency {
+        for key in validator.table_keys(&keys) {
+            if !DEPENDENCY_KEYS.contains(&key.as_str()) {
+                validator.unused_key(&[table.to_string(), name.to_string(), key]);
+            }
+        }
+    }
+
+    let coordinates = match dependency.coordinates(name) {
+        Ok(coordinates) => coordinates,
+        Err(e) => {
+            validator.error(&keys, e.to_string());
+            return;
+        }
+    };
+    let part = Regex::new(r"^[A-Za-z0-9_.-]+$").unwrap();
+    // Versions may also be ranges such as `[1.0,2.0)`
+    let version = Regex::new(r"^[A-Za-z0-9_.+\-\[\](),]+$").unwrap();
+    let invalid = !part.is_match(&coordinates.group)
+        || !part.is_match(&coordinates.artifact)
+        || !version.is_match(&coordinates.version)
+        || coordinates
+            .classifier
+            .as_ref()
+            .is_some_and(|classifier| !part.is_match(classifier));
+    if invalid {
+        validator.error(
+            &keys,
+            format!(
+                "dependency `{}` has invalid Maven coordinates `{}`",
+                name,
+                coordinates.notation()
+            ),
+        );
+    }
+
+    if let Err(e) = dependency.exclusions(name) {
+        validator.error(&[table, name, "exclude"], e.to_string());
+    } else if let Dependency::Detailed(detailed) = dependency {
+        for (index, exclude) in detailed.exclude.iter().enumerate() {
+            if !exclude.split(':').all(|part_| part.is_match(part_)) {
+                validator.error(
+                    &[table, name, "exclude", &index.to_string()],
+                    format!("dependency `{}` excludes invalid `{}`", name, exclude),
+                );
+            }
+        }
+    }
+}
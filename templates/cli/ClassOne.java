@@ -0,0 +1,7 @@
+package {{namespace}}.classone;
+
+public class ClassOne {
+    public static void oneMethod() {
+        System.out.println("ClassOne method");
+    }
+}
@@ -0,0 +1,7 @@
+package {{namespace}}.classtwo;
+
+public class ClassTwo {
+    public static void twoMethod() {
+        System.out.println("ClassTwo method");
+    }
+}
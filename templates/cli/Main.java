@@ -0,0 +1,11 @@
+package {{main_package}};
+
+import {{namespace}}.classone.ClassOne;
+import {{namespace}}.classtwo.ClassTwo;
+
+public class {{main_class_name}} {
+    public static void main(String[] args) {
+        ClassOne.oneMethod();
+        ClassTwo.twoMethod();
+    }
+}
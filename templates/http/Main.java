@@ -0,0 +1,16 @@
+package {{main_package}};
+
+import {{namespace}}.handlers.HelloHandler;
+import com.sun.net.httpserver.HttpServer;
+import java.io.IOException;
+import java.net.InetSocketAddress;
+
+public class {{main_class_name}} {
+    public static void main(String[] args) throws IOException {
+        int port = Integer.parseInt(System.getenv().getOrDefault("PORT", "8080"));
+        HttpServer server = HttpServer.create(new InetSocketAddress(port), 0);
+        server.createContext("/", new HelloHandler());
+        server.start();
+        System.out.println("{{name}} listening on http://localhost:" + port + "/");
+    }
+}
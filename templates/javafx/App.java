@@ -0,0 +1,25 @@
+package {{namespace}}.ui;
+
+import javafx.application.Application;
+import javafx.scene.Scene;
+import javafx.scene.control.Button;
+import javafx.scene.control.Label;
+import javafx.scene.layout.VBox;
+import javafx.stage.Stage;
+
+public class App extends Application {
+    private int clicks;
+
+    @Override
+    public void start(Stage stage) {
+        Label label = new Label("Hello from {{name}}!");
+        Button button = new Button("Click me");
+        button.setOnAction(event -> label.setText("Clicked " + ++clicks + " times"));
+
+        VBox root = new VBox(12, label, button);
+        root.setStyle("-fx-padding: 24; -fx-alignment: center;");
+        stage.setTitle("{{name}}");
+        stage.setScene(new Scene(root, 320, 200));
+        stage.show();
+    }
+}
@@ -0,0 +1,12 @@
+package {{main_package}};
+
+import {{namespace}}.ui.App;
+import javafx.application.Application;
+
+// JavaFX refuses to start from the classpath when the main class extends
+// Application, so a plain class launches it
+public class {{main_class_name}} {
+    public static void main(String[] args) {
+        Application.launch(App.class, args);
+    }
+}
@@ -0,0 +1,16 @@
+package {{namespace}};
+
+/**
+ * Entry point of the {{name}} library.
+ */
+public class Library {
+    /**
+     * Greet someone by name.
+     *
+     * @param name who to greet
+     * @return the greeting
+     */
+    public static String greet(String name) {
+        return "Hello, " + name + "!";
+    }
+}
@@ -0,0 +1,9 @@
+package {{main_package}};
+
+import {{namespace}}.Library;
+
+public class {{main_class_name}} {
+    public static void main(String[] args) {
+        System.out.println(Library.greet(args.length > 0 ? args[0] : "world"));
+    }
+}
@@ -0,0 +1,30 @@
+package {{main_package}};
+
+import java.util.concurrent.Callable;
+import picocli.CommandLine;
+import picocli.CommandLine.Command;
+import picocli.CommandLine.Option;
+import picocli.CommandLine.Parameters;
+
+@Command(name = "{{name}}", mixinStandardHelpOptions = true, version = "{{name}} {{version}}",
+        description = "Greets everyone it is given.")
+public class {{main_class_name}} implements Callable<Integer> {
+    @Parameters(description = "Who to greet.", defaultValue = "world")
+    private String[] names;
+
+    @Option(names = {"-s", "--shout"}, description = "Greet in upper case.")
+    private boolean shout;
+
+    @Override
+    public Integer call() {
+        for (String name : names) {
+            String greeting = "Hello, " + name + "!";
+            System.out.println(shout ? greeting.toUpperCase() : greeting);
+        }
+        return 0;
+    }
+
+    public static void main(String[] args) {
+        System.exit(new CommandLine(new {{main_class_name}}()).execute(args));
+    }
+}
@@ -0,0 +1,133 @@
+mod common;
+
+use serial_test::serial;
+use std::fs;
+use std::process::Command;
+
+const RSJ_TOML: &str = r#"[project]
+name = "multi"
+version = "1.0.0"
+main_class = "Main"
+base_namespace = "com.example"
+backend = "javac"
+
+[[bin]]
+name = "admin"
+main_class = "tools.Admin"
+"#;
+
+fn echo_class(name: &str) -> String {
+    format!(
+        "public class {} {{\n    public static void main(String[] args) {{\n        System.out.println(\"{} \" + String.join(\",\", args));\n    }}\n}}\n",
+        name, name
+    )
+}
+
+fn create_project(name: &str, toml: &str) -> std::path::PathBuf {
+    common::create_test_project(
+        name,
+        &[
+            ("rsj.toml", toml),
+            ("src/Main.java", &echo_class("Main")),
+            ("src/bin/Tool.java", &echo_class("Tool")),
+            ("src/tools/Admin.java", &echo_class("Admin")),
+        ],
+    )
+    .unwrap()
+}
+
+// Test that `rsj build` writes a start script per binary
+#[test]
+#[serial]
+fn test_build_writes_start_scripts() {
+    let test_dir = create_project("binaries_build_test", RSJ_TOML);
+
+    let output = common::rsj_output(&test_dir, &["build"]).unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stdout)
+    );
+
+    let bin_dir = test_dir.join("target/dev/bin");
+    for name in ["multi", "admin", "Tool"] {
+        assert!(bin_dir.join(name).is_file(), "missing {}", name);
+        assert!(bin_dir.join(format!("{}.bat", name)).is_file());
+    }
+
+    // Scripts work from any directory
+    let output = Command::new(bin_dir.join("admin"))
+        .args(["a", "b"])
+        .current_dir(std::env::temp_dir())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "Admin a,b\n");
+
+    // Scripts of removed binaries are cleaned up
+    fs::remove_file(test_dir.join("src/bin/Tool.java")).unwrap();
+    let output = common::rsj_output(&test_dir, &["build"]).unwrap();
+    assert!(output.status.success());
+    assert!(!bin_dir.join("Tool").exists());
+    assert!(bin_dir.join("admin").exists());
+}
+
+// Test that `rsj run --bin` selects the binary to launch
+#[test]
+#[serial]
+fn test_run_selects_binary() {
+    let test_dir = create_project("binaries_run_test", RSJ_TOML);
+
+    let run = |args: &[&str]| {
+        let output = common::rsj_output(&test_dir, args).unwrap();
+        (
+            output.status.code(),
+            String::from_utf8_lossy(&output.stdout).to_string(),
+        )
+    };
+
+    let (code, stdout) = run(&["run"]);
+    assert_eq!(code, Some(0), "{}", stdout);
+    assert!(stdout.contains("Main \n"), "{}", stdout);
+
+    let (code, stdout) = run(&["run", "--bin", "Tool"]);
+    assert_eq!(code, Some(0), "{}", stdout);
+    assert!(stdout.contains("Tool \n"), "{}", stdout);
+
+    let (code, stdout) = run(&["run", "--bin", "admin", "--", "x"]);
+    assert_eq!(code, Some(0), "{}", stdout);
+    assert!(stdout.contains("Admin x\n"), "{}", stdout);
+
+    let (code, stdout) = run(&["run", "--bin", "nope"]);
+    assert_eq!(code, Some(2), "{}", stdout);
+    assert!(
+        stdout.contains("Available binaries: multi, admin, Tool"),
+        "{}",
+        stdout
+    );
+}
+
+// Test that binary names must be unique and main classes must exist
+#[test]
+#[serial]
+fn test_invalid_binaries() {
+    let toml = format!(
+        "{}\n[[bin]]\nname = \"admin\"\nmain_class = \"tools.Missing\"\n",
+        RSJ_TOML
+    );
+    let test_dir = create_project("binaries_invalid_test", &toml);
+
+    let output = common::rsj_output(&test_dir, &["build"]).unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(output.status.code(), Some(2), "{}", stdout);
+    assert!(
+        stdout.contains("binary name `admin` is used more than once"),
+        "{}",
+        stdout
+    );
+    assert!(
+        stdout.contains("`main_class` `tools.Missing` was not found"),
+        "{}",
+        stdout
+    );
+}
@@ -0,0 +1,149 @@
+mod common;
+
+use serial_test::serial;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+const RSJ_TOML: &str = r#"[project]
+name = "info"
+version = "1.2.3"
+main_class = "Main"
+base_namespace = "com.example"
+backend = "javac"
+
+[build_info]
+features = ["metrics", "tracing"]
+"#;
+
+const MAIN: &str = r#"package com.example;
+
+public class Main {
+    public static void main(String[] args) {
+        System.out.println(BuildInfo.NAME + " " + BuildInfo.VERSION);
+        System.out.println("commit " + BuildInfo.GIT_COMMIT + " dirty " + BuildInfo.GIT_DIRTY);
+        System.out.println("built " + BuildInfo.BUILD_TIMESTAMP);
+        System.out.println("features " + BuildInfo.FEATURES);
+    }
+}
+"#;
+
+fn git(dir: &Path, args: &[&str]) -> String {
+    let output = Command::new("git")
+        .args(["-c", "user.name=rsj", "-c", "user.email=rsj@example.com"])
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "git {:?}", args);
+    String::from_utf8_lossy(&output.stdout).trim().to_string()
+}
+
+fn run(dir: &Path) -> String {
+    let output = common::rsj_command(dir, &["run"])
+        .unwrap()
+        .env("SOURCE_DATE_EPOCH", "1700000000")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    assert!(output.status.success(), "{}", stdout);
+    stdout
+}
+
+// Test that the generated class reports the version, commit and timestamp
+#[test]
+#[serial]
+fn test_build_info_from_git() {
+    let scratch = tempfile::tempdir().unwrap();
+    let project_dir = scratch.path();
+    fs::create_dir_all(project_dir.join("src")).unwrap();
+    fs::write(project_dir.join("rsj.toml"), RSJ_TOML).unwrap();
+    fs::write(project_dir.join("src/Main.java"), MAIN).unwrap();
+    git(project_dir, &["init", "--quiet"]);
+    git(project_dir, &["add", "."]);
+    git(project_dir, &["commit", "--quiet", "-m", "Initial"]);
+    let commit = git(project_dir, &["rev-parse", "HEAD"]);
+
+    let stdout = run(project_dir);
+    assert!(stdout.contains("info 1.2.3\n"), "{}", stdout);
+    assert!(
+        stdout.contains(&format!("commit {} dirty false\n", commit)),
+        "{}",
+        stdout
+    );
+    assert!(
+        stdout.contains("built 2023-11-14T22:13:20Z\n"),
+        "{}",
+        stdout
+    );
+    assert!(
+        stdout.contains("features [metrics, tracing]\n"),
+        "{}",
+        stdout
+    );
+
+    // The rsj.lock written by the first build does not count as a change
+    assert!(project_dir.join("rsj.lock").is_file());
+    let stdout = run(project_dir);
+    assert!(
+        stdout.contains(&format!("commit {} dirty false\n", commit)),
+        "{}",
+        stdout
+    );
+
+    // Uncommitted changes mark the build dirty
+    fs::write(project_dir.join("README.md"), "# info\n").unwrap();
+    let stdout = run(project_dir);
+    assert!(
+        stdout.contains(&format!("commit {} dirty true\n", commit)),
+        "{}",
+        stdout
+    );
+}
+
+// Test that the timestamp alone does not make the next build recompile
+#[test]
+#[serial]
+fn test_build_info_stays_fresh() {
+    let test_dir = common::create_test_project(
+        "build_info_fresh_test",
+        &[("rsj.toml", RSJ_TOML), ("src/Main.java", MAIN)],
+    )
+    .unwrap();
+
+    let output = common::rsj_output(&test_dir, &["build"]).unwrap();
+    assert!(output.status.success());
+    let generated = test_dir.join("rsj_build/javac/src/com/example/BuildInfo.java");
+    let first = fs::read_to_string(&generated).unwrap();
+    assert!(first.contains("package com.example;"), "{}", first);
+
+    std::thread::sleep(std::time::Duration::from_millis(1100));
+    let output = common::rsj_output(&test_dir, &["build"]).unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Build is up to date."), "{}", stdout);
+    assert_eq!(fs::read_to_string(&generated).unwrap(), first);
+}
+
+// Test that the class must not replace a source file
+#[test]
+#[serial]
+fn test_build_info_clash() {
+    let test_dir = common::create_test_project(
+        "build_info_clash_test",
+        &[
+            ("rsj.toml", RSJ_TOML),
+            ("src/Main.java", MAIN),
+            ("src/BuildInfo.java", "public class BuildInfo {}\n"),
+        ],
+    )
+    .unwrap();
+
+    let output = common::rsj_output(&test_dir, &["build"]).unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(output.status.code(), Some(2), "{}", stdout);
+    assert!(
+        stdout.contains("clashes with `src/BuildInfo.java`"),
+        "{}",
+        stdout
+    );
+}
@@ -6,6 +6,7 @@ use std::process::Command;
 const BINARY_NAME: &str = "rsj";
 
 // Helper function to run a command in a specific directory
+#[allow(dead_code)]
 pub fn run_command_in_dir(dir: &Path, program: &str, args: &[&str]) -> Result<(), String> {
     let output = Command::new(program)
         .current_dir(dir)
@@ -24,7 +25,17 @@ pub fn run_command_in_dir(dir: &Path, program: &str, args: &[&str]) -> Result<()
     Ok(())
 }
 
+// Find a program on PATH, like a shell would
+#[allow(dead_code)]
+pub fn find_program(name: &str) -> Option<std::path::PathBuf> {
+    let path = env::var_os("PATH")?;
+    env::split_paths(&path)
+        .map(|dir| dir.join(name))
+        .find(|candidate| candidate.is_file())
+}
+
 // Helper function to clean up build artifacts after a test
+#[allow(dead_code)]
 pub fn cleanup_build_dir(project_dir: &Path) {
     let build_dir = project_dir.join("rsj_build");
     if build_dir.exists() {
@@ -81,7 +92,7 @@ fn find_binary_path() -> Result<std::path::PathBuf, String> {
     // If we can't find it, build it
     println!("Binary not found, attempting to build it...");
     let status = Command::new("cargo")
-        .args(&["build"])
+        .args(["build"])
         .current_dir(&manifest_dir)
         .status()
         .map_err(|_| "Failed to build binary".to_string())?;
@@ -99,8 +110,101 @@ fn find_binary_path() -> Result<std::path::PathBuf, String> {
 }
 
 // Execute rsj command on an example project
+#[allow(dead_code)]
 pub fn run_rsj_command(example_path: &Path, command: &str) -> Result<(), String> {
     let bin_path = find_binary_path()?;
 
     run_command_in_dir(example_path, bin_path.to_str().unwrap(), &[command])
 }
+
+// Create a scratch project under target/test_projects from (path, content) pairs
+#[allow(dead_code)]
+pub fn create_test_project(
+    project_name: &str,
+    files: &[(&str, &str)],
+) -> Result<std::path::PathBuf, String> {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR")
+        .map_err(|_| "Failed to get CARGO_MANIFEST_DIR".to_string())?;
+
+    let test_dir = Path::new(&manifest_dir)
+        .join("target")
+        .join("test_projects")
+        .join(project_name);
+
+    // Start from a clean slate
+    if test_dir.exists() {
+        fs::remove_dir_all(&test_dir)
+            .map_err(|e| format!("Failed to remove old test directory: {}", e))?;
+    }
+
+    for (relative_path, content) in files {
+        let path = test_dir.join(relative_path);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+        fs::write(&path, content)
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    }
+
+    Ok(test_dir)
+}
+
+// Prepare an rsj command in the given directory, for tests that need to tweak it
+#[allow(dead_code)]
+pub fn rsj_command(dir: &Path, args: &[&str]) -> Result<Command, String> {
+    let bin_path = find_binary_path()?;
+
+    let mut command = Command::new(bin_path);
+    command.current_dir(dir).args(args);
+    Ok(command)
+}
+
+// Run rsj with the given arguments and return its output, whatever the exit status
+#[allow(dead_code)]
+pub fn rsj_output(dir: &Path, args: &[&str]) -> Result<std::process::Output, String> {
+    rsj_command(dir, args)?
+        .output()
+        .map_err(|e| format!("Failed to execute command: {}", e))
+}
+
+// Minimal stand-in for Gradle: compile with javac and package a runnable JAR
+const FAKE_GRADLE: &str = r#"#!/bin/sh
+set -e
+name=$(sed -n "s/^rootProject.name = '\(.*\)'/\1/p" settings.gradle)
+version=$(sed -n "s/^version = '\(.*\)'/\1/p" build.gradle)
+main=$(sed -n "s/^    mainClass = '\(.*\)'/\1/p" build.gradle)
+mkdir -p build/classes build/libs
+javac -d build/classes $(find src/main/java -name '*.java')
+jar --create --file "build/libs/$name-$version.jar" --main-class "$main" -C build/classes .
+: > build/rsj-resolved.txt
+"#;
+
+// Install the fake Gradle into the test project and return a PATH that prefers it
+#[allow(dead_code)]
+pub fn fake_gradle_path(test_dir: &Path) -> Result<String, String> {
+    fake_tool_path(test_dir, "gradle", FAKE_GRADLE)
+}
+
+// Install a shell script as `name` into the test project and return a PATH that prefers it
+#[allow(dead_code)]
+pub fn fake_tool_path(test_dir: &Path, name: &str, script: &str) -> Result<String, String> {
+    let bin_dir = test_dir.join("fake_bin");
+    fs::create_dir_all(&bin_dir).map_err(|e| format!("Failed to create fake_bin: {}", e))?;
+
+    let tool = bin_dir.join(name);
+    fs::write(&tool, script).map_err(|e| format!("Failed to write fake {}: {}", name, e))?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&tool, fs::Permissions::from_mode(0o755))
+            .map_err(|e| format!("Failed to make fake {} executable: {}", name, e))?;
+    }
+
+    Ok(format!(
+        "{}:{}",
+        bin_dir.display(),
+        env::var("PATH").unwrap_or_default()
+    ))
+}
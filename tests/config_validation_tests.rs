@@ -0,0 +1,149 @@
+mod common;
+
+use serial_test::serial;
+
+const MAIN_JAVA: &str = r#"public class Main {
+    public static void main(String[] args) {
+        System.out.println("validated");
+    }
+}
+"#;
+
+// Test that invalid values are reported together, each with its line of rsj.toml
+#[test]
+#[serial]
+fn test_invalid_values_point_at_manifest_lines() {
+    let toml = r#"[project]
+name = "validation"
+version = "one"
+main_class = "Main"
+base_namespace = "com.1example"
+gradle_version = "latest"
+backend = "javac"
+
+[dependencies]
+gson = "com.google code.gson:gson:2.10.1"
+"#;
+    let test_dir = common::create_test_project(
+        "config_validation_test",
+        &[("rsj.toml", toml), ("src/Main.java", MAIN_JAVA)],
+    )
+    .unwrap();
+
+    let output = common::rsj_output(&test_dir, &["build"]).unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(output.status.code(), Some(2), "{}", stdout);
+    for expected in [
+        "invalid `version` `one`",
+        "--> rsj.toml:3:11",
+        "3 | version = \"one\"",
+        "^^^^^",
+        "invalid `base_namespace` `com.1example`",
+        "--> rsj.toml:5:18",
+        "invalid `gradle_version` `latest`",
+        "--> rsj.toml:6:18",
+        "dependency `gson` has invalid Maven coordinates",
+        "--> rsj.toml:10:8",
+        "due to 4 previous errors",
+    ] {
+        assert!(
+            stdout.contains(expected),
+            "missing {:?} in\n{}",
+            expected,
+            stdout
+        );
+    }
+    assert!(!test_dir.join("rsj_build").exists());
+}
+
+// Test that a `main_class` without a source file fails before building
+#[test]
+#[serial]
+fn test_missing_main_class() {
+    let toml = r#"[project]
+name = "validation"
+version = "1.0.0"
+main_class = "app.Launcher"
+base_namespace = "com.example"
+backend = "javac"
+"#;
+    let test_dir = common::create_test_project(
+        "config_missing_main_test",
+        &[("rsj.toml", toml), ("src/Main.java", MAIN_JAVA)],
+    )
+    .unwrap();
+
+    let output = common::rsj_output(&test_dir, &["build"]).unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(output.status.code(), Some(2), "{}", stdout);
+    assert!(
+        stdout.contains(
+            "`main_class` `app.Launcher` was not found, expected `src/app/Launcher.java`"
+        ),
+        "{}",
+        stdout
+    );
+}
+
+// Test that unknown keys are warned about without failing the build
+#[test]
+#[serial]
+fn test_unknown_keys_warn() {
+    let toml = r#"[project]
+name = "validation"
+version = "1.0.0"
+main_class = "Main"
+base_namespace = "com.example"
+backend = "javac"
+use_shadw = false
+
+[dependencies]
+
+[tset]
+inline = true
+"#;
+    let test_dir = common::create_test_project(
+        "config_unknown_keys_test",
+        &[("rsj.toml", toml), ("src/Main.java", MAIN_JAVA)],
+    )
+    .unwrap();
+
+    let output = common::rsj_output(&test_dir, &["build"]).unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{}", stdout);
+    for expected in [
+        "warning: unused manifest key: project.use_shadw",
+        "--> rsj.toml:7:1",
+        "warning: unused manifest key: tset",
+        "--> rsj.toml:11:2",
+    ] {
+        assert!(
+            stdout.contains(expected),
+            "missing {:?} in\n{}",
+            expected,
+            stdout
+        );
+    }
+}
+
+// Test that TOML syntax errors are shown at their position
+#[test]
+#[serial]
+fn test_syntax_error_location() {
+    let toml = "[project]\nname = \"validation\"\nversion = 1.0.0\n";
+    let test_dir = common::create_test_project(
+        "config_syntax_error_test",
+        &[("rsj.toml", toml), ("src/Main.java", MAIN_JAVA)],
+    )
+    .unwrap();
+
+    let output = common::rsj_output(&test_dir, &["build"]).unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(output.status.code(), Some(2), "{}", stdout);
+    assert!(stdout.contains("--> rsj.toml:3:"), "{}", stdout);
+    assert!(
+        stdout.contains("Invalid TOML format in `rsj.toml`."),
+        "{}",
+        stdout
+    );
+}
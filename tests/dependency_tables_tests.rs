@@ -0,0 +1,193 @@
+mod common;
+
+use serial_test::serial;
+use std::fs;
+use std::process::Command;
+
+const MAIN_JAVA: &str = r#"public class Main {
+    public static void main(String[] args) {
+        System.out.println("tables");
+    }
+}
+"#;
+
+const DEPENDENCIES: &str = r#"
+[dependencies]
+gson = "com.google.code.gson:gson:2.10.1"
+guava = { group = "com.google.guava", version = "33.0-jre", scope = "api", exclude = ["com.google.code.findbugs:jsr305", "org.checkerframework"] }
+servlet = { group = "jakarta.servlet", artifact = "jakarta.servlet-api", version = "6.0.0", scope = "compileOnly" }
+natives = { group = "org.lwjgl", artifact = "lwjgl", version = "3.3.3", classifier = "natives-linux", optional = true }
+
+[dev-dependencies]
+assertj = { group = "org.assertj", artifact = "assertj-core", version = "3.25.1" }
+
+[build-dependencies]
+autoservice = { group = "com.google.auto.service", artifact = "auto-service", version = "1.1.1" }
+"#;
+
+fn rsj_toml(backend: &str) -> String {
+    format!(
+        r#"[project]
+name = "tables"
+version = "1.0.0"
+main_class = "Main"
+base_namespace = "com.example"
+backend = "{}"
+{}"#,
+        backend, DEPENDENCIES
+    )
+}
+
+// Writes the JAR and an empty classpath listing without compiling anything
+const EMPTY_MVN: &str = r#"#!/bin/sh
+mkdir -p target
+touch target/tables-1.0.0.jar target/rsj-resolved.txt
+"#;
+
+// Test that detailed tables map to Gradle configurations and exclusions
+#[test]
+#[serial]
+fn test_dependency_tables_in_build_gradle() {
+    let toml = rsj_toml("gradle");
+    let test_dir = common::create_test_project(
+        "dependency_tables_gradle_test",
+        &[("rsj.toml", &toml), ("src/Main.java", MAIN_JAVA)],
+    )
+    .unwrap();
+    let path = common::fake_gradle_path(&test_dir).unwrap();
+
+    let output = common::rsj_command(&test_dir, &["build"])
+        .unwrap()
+        .env("PATH", path)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stdout)
+    );
+
+    let build = fs::read_to_string(test_dir.join("rsj_build/gradle/build.gradle")).unwrap();
+    for expected in [
+        "    implementation 'com.google.code.gson:gson:2.10.1'\n",
+        "    api('com.google.guava:guava:33.0-jre') {\n        exclude group: 'com.google.code.findbugs', module: 'jsr305'\n        exclude group: 'org.checkerframework'\n    }\n",
+        "    compileOnly 'jakarta.servlet:jakarta.servlet-api:6.0.0'\n",
+        "    implementation 'org.lwjgl:lwjgl:3.3.3:natives-linux'\n",
+        "    testImplementation 'org.assertj:assertj-core:3.25.1'\n",
+        "    annotationProcessor 'com.google.auto.service:auto-service:1.1.1'\n",
+    ] {
+        assert!(
+            build.contains(expected),
+            "missing {:?} in\n{}",
+            expected,
+            build
+        );
+    }
+}
+
+// Test that detailed tables map to POM scopes, exclusions and processor paths
+#[test]
+#[serial]
+fn test_dependency_tables_in_pom() {
+    let toml = rsj_toml("maven");
+    let test_dir = common::create_test_project(
+        "dependency_tables_maven_test",
+        &[("rsj.toml", &toml), ("src/Main.java", MAIN_JAVA)],
+    )
+    .unwrap();
+    let path = common::fake_tool_path(&test_dir, "mvn", EMPTY_MVN).unwrap();
+
+    let output = common::rsj_command(&test_dir, &["build"])
+        .unwrap()
+        .env("PATH", path)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stdout)
+    );
+
+    let pom = fs::read_to_string(test_dir.join("rsj_build/maven/pom.xml")).unwrap();
+    for expected in [
+        "<artifactId>jsr305</artifactId>",
+        "<groupId>org.checkerframework</groupId>\n          <artifactId>*</artifactId>",
+        "<artifactId>jakarta.servlet-api</artifactId>\n      <version>6.0.0</version>\n      <scope>provided</scope>",
+        "<classifier>natives-linux</classifier>\n      <optional>true</optional>",
+        "<artifactId>assertj-core</artifactId>\n      <version>3.25.1</version>\n      <scope>test</scope>",
+        "<annotationProcessorPaths>\n            <path>\n              <groupId>com.google.auto.service</groupId>",
+    ] {
+        assert!(pom.contains(expected), "missing {:?} in\n{}", expected, pom);
+    }
+}
+
+// Test that the javac backend keeps compile-only dependencies out of the fat JAR
+#[test]
+#[serial]
+fn test_javac_compile_only_dependency() {
+    let toml = r#"[project]
+name = "compile_only"
+version = "1.0.0"
+main_class = "Main"
+base_namespace = "com.example"
+backend = "javac"
+local_repository = "repo"
+
+[dependencies]
+greeter = { group = "com.acme", version = "1.0", scope = "compileOnly" }
+"#;
+    let main_java = r#"public class Main {
+    public static void main(String[] args) {
+        if (args.length > 0) {
+            System.out.println(com.acme.Greeter.greet(args[0]));
+        }
+        System.out.println("compiled");
+    }
+}
+"#;
+    let test_dir = common::create_test_project(
+        "javac_compile_only_test",
+        &[("rsj.toml", toml), ("src/Main.java", main_java)],
+    )
+    .unwrap();
+
+    // `com.acme:greeter:1.0` in a Maven-layout repository
+    let work = test_dir.join("greeter");
+    fs::create_dir_all(work.join("com/acme")).unwrap();
+    fs::write(
+        work.join("com/acme/Greeter.java"),
+        "package com.acme;\n\npublic class Greeter {\n    public static String greet(String name) {\n        return name;\n    }\n}\n",
+    )
+    .unwrap();
+    common::run_command_in_dir(&work, "javac", &["-d", "classes", "com/acme/Greeter.java"])
+        .unwrap();
+    let repo_dir = test_dir.join("repo/com/acme/greeter/1.0");
+    fs::create_dir_all(&repo_dir).unwrap();
+    common::run_command_in_dir(
+        &work,
+        "jar",
+        &[
+            "--create",
+            "--file",
+            repo_dir.join("greeter-1.0.jar").to_str().unwrap(),
+            "-C",
+            "classes",
+            ".",
+        ],
+    )
+    .unwrap();
+
+    let output = common::rsj_output(&test_dir, &["run"]).unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{}", stdout);
+    assert!(stdout.contains("compiled"), "{}", stdout);
+
+    let listing = Command::new("jar")
+        .args(["--list", "--file"])
+        .arg(test_dir.join("rsj_build/javac/libs/compile_only-1.0.0.jar"))
+        .output()
+        .unwrap();
+    let listing = String::from_utf8_lossy(&listing.stdout);
+    assert!(listing.contains("com/example/Main.class"), "{}", listing);
+    assert!(!listing.contains("com/acme/Greeter.class"), "{}", listing);
+}
@@ -0,0 +1,94 @@
+mod common;
+
+use serial_test::serial;
+use std::fs;
+use std::io::{BufRead, BufReader};
+use std::process::Stdio;
+
+// Test that javac errors point at the original files in `src`
+#[test]
+#[serial]
+fn test_diagnostics_point_at_original_sources() {
+    let test_dir = common::create_test_project(
+        "diagnostics_test",
+        &[
+            (
+                "rsj.toml",
+                "[project]\nname = \"diagnostics\"\nversion = \"1.0.0\"\nmain_class = \"Main\"\nbase_namespace = \"com.example\"\n",
+            ),
+            ("src/Main.java", "public class Main { int x = ; }\n"),
+            (
+                "src/util/Util.java",
+                "// Header\npackage util;\n\npublic class Util {\n    void f() { int y = ; }\n}\n",
+            ),
+        ],
+    )
+    .unwrap();
+
+    let path = common::fake_gradle_path(&test_dir).unwrap();
+    let output = common::rsj_command(&test_dir, &["build"])
+        .unwrap()
+        .env("PATH", path)
+        .env("NO_COLOR", "1")
+        .output()
+        .unwrap();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(
+        stderr.contains("--> src/Main.java:1:29"),
+        "Unexpected output: {}",
+        stderr
+    );
+    assert!(stderr.contains("1 | public class Main { int x = ; }"));
+    assert!(stderr.contains("--> src/util/Util.java:5:24"));
+    assert!(stderr.contains("5 |     void f() { int y = ; }"));
+    assert!(!stderr.contains("rsj_build"));
+    assert!(stdout.contains("compilation errors"), "{}", stdout);
+
+    let _ = fs::remove_dir_all(&test_dir);
+}
+
+// Test that the build tool's output is shown while it runs, not when it exits
+#[test]
+#[serial]
+fn test_build_output_is_streamed() {
+    let test_dir = common::create_test_project(
+        "diagnostics_streaming_test",
+        &[
+            (
+                "rsj.toml",
+                "[project]\nname = \"streaming\"\nversion = \"1.0.0\"\nmain_class = \"Main\"\nbase_namespace = \"com.example\"\n",
+            ),
+            ("src/Main.java", "public class Main {}\n"),
+        ],
+    )
+    .unwrap();
+    // Waits for the test to see its first line, and fails if that never happens
+    let path = common::fake_tool_path(
+        &test_dir,
+        "gradle",
+        "#!/bin/sh\necho '> Task :compileJava' >&2\ni=0\nwhile [ ! -f \"$RSJ_TEST_GO\" ]; do\n    i=$((i + 1))\n    [ $i -gt 100 ] && exit 1\n    sleep 0.1\ndone\nmkdir -p build\n: > build/rsj-resolved.txt\n",
+    )
+    .unwrap();
+    let go = test_dir.join("go");
+
+    let mut child = common::rsj_command(&test_dir, &["build"])
+        .unwrap()
+        .env("PATH", path)
+        .env("RSJ_TEST_GO", &go)
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let stderr = BufReader::new(child.stderr.take().unwrap());
+    for line in stderr.lines() {
+        if line.unwrap() == "> Task :compileJava" {
+            fs::write(&go, "").unwrap();
+        }
+    }
+    assert!(child.wait().unwrap().success());
+    assert!(go.exists());
+
+    let _ = fs::remove_dir_all(&test_dir);
+}
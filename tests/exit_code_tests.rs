@@ -0,0 +1,80 @@
+mod common;
+
+use serial_test::serial;
+use std::fs;
+
+const RSJ_TOML: &str = r#"[project]
+name = "exit_codes"
+version = "1.0.0"
+main_class = "Main"
+base_namespace = "com.example"
+"#;
+
+// Test that a missing rsj.toml is reported as a configuration error
+#[test]
+#[serial]
+fn test_missing_config_exit_code() {
+    let test_dir =
+        common::create_test_project("missing_config_test", &[("src/Main.java", "")]).unwrap();
+
+    let output = common::rsj_output(&test_dir, &["build"]).unwrap();
+    assert_eq!(output.status.code(), Some(2));
+
+    let _ = fs::remove_dir_all(&test_dir);
+}
+
+// Test that compilation failures exit with the build failure code
+#[test]
+#[serial]
+fn test_build_failure_exit_code() {
+    let test_dir = common::create_test_project(
+        "build_failure_test",
+        &[
+            ("rsj.toml", RSJ_TOML),
+            ("src/Main.java", "public class Main { oops }\n"),
+        ],
+    )
+    .unwrap();
+
+    let path = common::fake_gradle_path(&test_dir).unwrap();
+    let output = common::rsj_command(&test_dir, &["build"])
+        .unwrap()
+        .env("PATH", path)
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(101));
+
+    let _ = fs::remove_dir_all(&test_dir);
+}
+
+// Test that `rsj run` forwards the exit code of the Java application
+#[test]
+#[serial]
+fn test_run_forwards_exit_code() {
+    let test_dir = common::create_test_project(
+        "run_exit_code_test",
+        &[
+            ("rsj.toml", RSJ_TOML),
+            (
+                "src/Main.java",
+                "public class Main {\n    public static void main(String[] args) {\n        System.exit(42);\n    }\n}\n",
+            ),
+        ],
+    )
+    .unwrap();
+
+    let path = common::fake_gradle_path(&test_dir).unwrap();
+    let output = common::rsj_command(&test_dir, &["run"])
+        .unwrap()
+        .env("PATH", path)
+        .output()
+        .unwrap();
+    assert_eq!(
+        output.status.code(),
+        Some(42),
+        "{}",
+        String::from_utf8_lossy(&output.stdout)
+    );
+
+    let _ = fs::remove_dir_all(&test_dir);
+}
@@ -0,0 +1,85 @@
+mod common;
+
+use serial_test::serial;
+use std::fs;
+
+const MAIN_JAVA: &str = r#"public class Main {
+    public static void main(String[] args) {
+        System.out.println("escaped");
+    }
+}
+"#;
+
+// Test that quotes and `${}` from rsj.toml stay inside Groovy strings
+#[test]
+#[serial]
+fn test_gradle_values_are_escaped() {
+    let toml = r#"[project]
+name = "it's ${System.exit(1)}"
+version = "1.0.0"
+main_class = "Main"
+base_namespace = "com.example"
+"#;
+    let test_dir = common::create_test_project(
+        "gradle_escaping_test",
+        &[("rsj.toml", toml), ("src/Main.java", MAIN_JAVA)],
+    )
+    .unwrap();
+    let path = common::fake_gradle_path(&test_dir).unwrap();
+
+    let output = common::rsj_command(&test_dir, &["build"])
+        .unwrap()
+        .env("PATH", path)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stdout)
+    );
+
+    let settings = fs::read_to_string(test_dir.join("rsj_build/gradle/settings.gradle")).unwrap();
+    assert!(
+        settings.contains("rootProject.name = 'it\\'s ${System.exit(1)}'\n"),
+        "{}",
+        settings
+    );
+}
+
+// Test that names Java or Gradle cannot take are rejected before generating anything
+#[test]
+#[serial]
+fn test_invalid_identifiers_rejected() {
+    for (field, value) in [
+        ("base_namespace", "com.class"),
+        ("base_namespace", "com..example"),
+        ("main_class", "Main'"),
+        ("name", "a/b"),
+        ("gradle_version", "8.4/../../evil"),
+    ] {
+        let mut toml = String::from("[project]\n");
+        for (key, default) in [
+            ("name", "invalid"),
+            ("version", "1.0.0"),
+            ("main_class", "Main"),
+            ("base_namespace", "com.example"),
+        ] {
+            let value = if key == field { value } else { default };
+            toml.push_str(&format!("{} = \"{}\"\n", key, value));
+        }
+        if field == "gradle_version" {
+            toml.push_str(&format!("gradle_version = \"{}\"\n", value));
+        }
+
+        let test_dir = common::create_test_project(
+            "gradle_invalid_identifier_test",
+            &[("rsj.toml", &toml), ("src/Main.java", MAIN_JAVA)],
+        )
+        .unwrap();
+        let output = common::rsj_output(&test_dir, &["build"]).unwrap();
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert_eq!(output.status.code(), Some(2), "{}: {}", field, stdout);
+        assert!(stdout.contains(&format!("`{}`", field)), "{}", stdout);
+        assert!(!test_dir.join("rsj_build/gradle/build.gradle").exists());
+    }
+}
@@ -0,0 +1,253 @@
+// The fake Gradle is a shell script
+#![cfg(unix)]
+
+mod common;
+
+use serial_test::serial;
+use std::fs;
+use std::os::unix::fs::symlink;
+use std::path::Path;
+
+const MAIN_JAVA: &str = r#"public class Main {
+    public static void main(String[] args) {
+        System.out.println("wrapped");
+    }
+}
+"#;
+
+// Stand-in for Gradle that logs its invocations, implements `gradle wrapper` by
+// copying itself to `gradlew`, and otherwise compiles and packages the project
+const WRAPPING_GRADLE: &str = r#"#!/bin/sh
+set -e
+echo "$(basename "$0") $*" >> "$GRADLE_LOG"
+if [ "$1" = wrapper ]; then
+    mkdir -p gradle/wrapper
+    cp "$0" gradlew
+    echo '@rem fake' > gradlew.bat
+    echo 'fake' > gradle/wrapper/gradle-wrapper.jar
+    exit 0
+fi
+name=$(sed -n "s/^rootProject.name = '\(.*\)'/\1/p" settings.gradle)
+version=$(sed -n "s/^version = '\(.*\)'/\1/p" build.gradle)
+main=$(sed -n "s/^    mainClass = '\(.*\)'/\1/p" build.gradle)
+mkdir -p build/classes build/libs
+javac -d build/classes $(find src/main/java -name '*.java')
+jar --create --file "build/libs/$name-$version.jar" --main-class "$main" -C build/classes .
+: > build/rsj-resolved.txt
+"#;
+
+// A PATH with only the tools the fake Gradle needs, so no real Gradle is found
+fn minimal_path(test_dir: &Path) -> String {
+    let bin_dir = test_dir.join("tools");
+    fs::create_dir_all(&bin_dir).unwrap();
+    for tool in [
+        "basename", "cp", "find", "jar", "java", "javac", "mkdir", "sed",
+    ] {
+        let program = common::find_program(tool).unwrap_or_else(|| panic!("{} on PATH", tool));
+        symlink(program, bin_dir.join(tool)).unwrap();
+    }
+    bin_dir.display().to_string()
+}
+
+fn install_fake(path: &Path) {
+    use std::os::unix::fs::PermissionsExt;
+
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, WRAPPING_GRADLE).unwrap();
+    fs::set_permissions(path, fs::Permissions::from_mode(0o755)).unwrap();
+}
+
+// Test that the wrapper is generated once by an installed Gradle and used from then on
+#[test]
+#[serial]
+fn test_wrapper_generated_from_installed_gradle() {
+    let checksum = "a".repeat(64);
+    let toml = format!(
+        r#"[project]
+name = "wrapped"
+version = "1.0.0"
+main_class = "Main"
+base_namespace = "com.example"
+gradle_version = "8.5"
+gradle_distribution_url = "dist/gradle-8.5-bin.zip"
+gradle_distribution_sha256 = "{}"
+"#,
+        checksum
+    );
+    let test_dir = common::create_test_project(
+        "gradle_wrapper_generation_test",
+        &[
+            ("rsj.toml", &toml),
+            ("src/Main.java", MAIN_JAVA),
+            ("dist/gradle-8.5-bin.zip", "zip"),
+        ],
+    )
+    .unwrap();
+    install_fake(&test_dir.join("fake_bin/gradle"));
+    let path = format!(
+        "{}:{}",
+        test_dir.join("fake_bin").display(),
+        minimal_path(&test_dir)
+    );
+    let log = test_dir.join("gradle.log");
+
+    let build = || {
+        let output = common::rsj_command(&test_dir, &["build"])
+            .unwrap()
+            .env("PATH", &path)
+            .env("GRADLE_USER_HOME", test_dir.join("gradle_home"))
+            .env("GRADLE_LOG", &log)
+            .output()
+            .unwrap();
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stdout)
+        );
+    };
+
+    build();
+    assert_eq!(
+        fs::read_to_string(&log).unwrap(),
+        "gradle wrapper --gradle-version 8.5 --distribution-type bin\ngradlew shadowJar rsjResolve\n"
+    );
+    let gradle_dir = test_dir.join("rsj_build/gradle");
+    assert!(
+        gradle_dir
+            .join("gradle/wrapper/gradle-wrapper.jar")
+            .exists()
+    );
+    assert!(gradle_dir.join("gradlew.bat").exists());
+
+    let properties =
+        fs::read_to_string(gradle_dir.join("gradle/wrapper/gradle-wrapper.properties")).unwrap();
+    let zip = test_dir
+        .join("dist/gradle-8.5-bin.zip")
+        .canonicalize()
+        .unwrap();
+    assert!(
+        properties.contains(&format!("distributionUrl=file://{}\n", zip.display())),
+        "{}",
+        properties
+    );
+    assert!(
+        properties.contains(&format!("distributionSha256Sum={}\n", checksum)),
+        "{}",
+        properties
+    );
+
+    // The existing wrapper is reused
+    fs::write(
+        test_dir.join("src/Main.java"),
+        MAIN_JAVA.replace("wrapped", "again"),
+    )
+    .unwrap();
+    build();
+    let log = fs::read_to_string(&log).unwrap();
+    assert_eq!(log.matches("wrapper").count(), 1, "{}", log);
+    assert!(log.ends_with("gradlew shadowJar rsjResolve\n"), "{}", log);
+}
+
+// Test that a distribution in the wrapper cache works without any Gradle installed
+#[test]
+#[serial]
+fn test_preseeded_distribution() {
+    let toml = r#"[project]
+name = "preseeded"
+version = "1.0.0"
+main_class = "Main"
+base_namespace = "com.example"
+"#;
+    let test_dir = common::create_test_project(
+        "gradle_preseeded_distribution_test",
+        &[("rsj.toml", toml), ("src/Main.java", MAIN_JAVA)],
+    )
+    .unwrap();
+    let path = minimal_path(&test_dir);
+    let gradle_home = test_dir.join("gradle_home");
+    let log = test_dir.join("gradle.log");
+    let rsj = || {
+        common::rsj_command(&test_dir, &["build"])
+            .unwrap()
+            .env("PATH", &path)
+            .env("GRADLE_USER_HOME", &gradle_home)
+            .env("GRADLE_LOG", &log)
+            .output()
+            .unwrap()
+    };
+
+    // Neither Gradle nor a distribution
+    let output = rsj();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(output.status.code(), Some(127), "{}", stdout);
+    assert!(stdout.contains("Gradle wrapper cache"), "{}", stdout);
+
+    install_fake(
+        &gradle_home.join("wrapper/dists/gradle-8.14.3-bin/3abc/gradle-8.14.3/bin/gradle"),
+    );
+    let output = rsj();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stdout)
+    );
+    assert_eq!(
+        fs::read_to_string(&log).unwrap(),
+        "gradle wrapper --gradle-version 8.14.3 --distribution-type bin\ngradlew shadowJar rsjResolve\n"
+    );
+}
+
+// Test that without `gradle_version` the wrapper runs the installed Gradle's version,
+// unless that is too old for current JDKs
+#[test]
+#[serial]
+fn test_wrapper_defaults_to_installed_version() {
+    let toml = r#"[project]
+name = "installed"
+version = "1.0.0"
+main_class = "Main"
+base_namespace = "com.example"
+"#;
+    for (installed, expected) in [("8.13", "8.13"), ("8.4", "8.14.3")] {
+        let test_dir = common::create_test_project(
+            "gradle_wrapper_installed_test",
+            &[("rsj.toml", toml), ("src/Main.java", MAIN_JAVA)],
+        )
+        .unwrap();
+        let gradle_home = test_dir.join(format!("gradle-{}", installed));
+        install_fake(&gradle_home.join("bin/gradle"));
+        fs::create_dir_all(gradle_home.join("lib")).unwrap();
+        fs::write(
+            gradle_home.join(format!("lib/gradle-launcher-{}.jar", installed)),
+            "",
+        )
+        .unwrap();
+        let log = test_dir.join("gradle.log");
+
+        let output = common::rsj_command(&test_dir, &["build"])
+            .unwrap()
+            .env(
+                "PATH",
+                format!(
+                    "{}:{}",
+                    gradle_home.join("bin").display(),
+                    minimal_path(&test_dir)
+                ),
+            )
+            .env("GRADLE_USER_HOME", test_dir.join("gradle_user_home"))
+            .env("GRADLE_LOG", &log)
+            .output()
+            .unwrap();
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stdout)
+        );
+        let log = fs::read_to_string(&log).unwrap();
+        assert!(
+            log.starts_with(&format!("gradle wrapper --gradle-version {} ", expected)),
+            "{}",
+            log
+        );
+    }
+}
@@ -0,0 +1,94 @@
+mod common;
+
+use serial_test::serial;
+use std::fs;
+
+const RSJ_TOML: &str = r#"[project]
+name = "incremental"
+version = "1.0.0"
+main_class = "Main"
+base_namespace = "com.example"
+"#;
+
+// Stand-in for Gradle that counts its invocations and produces a JAR
+const COUNTING_GRADLE: &str = r#"#!/bin/sh
+echo run >> ../invocations.txt
+mkdir -p build/libs
+touch build/libs/incremental-1.0.0.jar build/rsj-resolved.txt
+"#;
+
+// Test that unchanged projects skip Gradle and deleted sources are pruned
+#[test]
+#[serial]
+fn test_incremental_build() {
+    let test_dir = common::create_test_project(
+        "incremental_build_test",
+        &[
+            ("rsj.toml", RSJ_TOML),
+            ("src/Main.java", "public class Main {}\n"),
+            ("src/util/Util.java", "public class Util {}\n"),
+            ("src/util/Other.java", "public class Other {}\n"),
+        ],
+    )
+    .unwrap();
+    let path = common::fake_tool_path(&test_dir, "gradle", COUNTING_GRADLE).unwrap();
+    let build = || {
+        let output = common::rsj_command(&test_dir, &["build"])
+            .unwrap()
+            .env("PATH", &path)
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        String::from_utf8_lossy(&output.stdout).to_string()
+    };
+    let invocations = || {
+        fs::read_to_string(test_dir.join("rsj_build/invocations.txt"))
+            .unwrap()
+            .lines()
+            .count()
+    };
+
+    build();
+    assert_eq!(invocations(), 1);
+
+    let java_dir = test_dir.join("rsj_build/gradle/src/main/java/com/example");
+    let main_modified = fs::metadata(java_dir.join("Main.java"))
+        .unwrap()
+        .modified()
+        .unwrap();
+
+    // Nothing changed, so Gradle is not invoked again
+    let stdout = build();
+    assert!(stdout.contains("Build is up to date."), "{}", stdout);
+    assert_eq!(invocations(), 1);
+
+    // Deleting a source removes its copy, and only changed files are rewritten
+    fs::remove_file(test_dir.join("src/util/Other.java")).unwrap();
+    fs::write(
+        test_dir.join("src/util/Util.java"),
+        "public class Util { int x; }\n",
+    )
+    .unwrap();
+    build();
+    assert_eq!(invocations(), 2);
+    assert!(!java_dir.join("util/Other.java").exists());
+    assert!(
+        fs::read_to_string(java_dir.join("util/Util.java"))
+            .unwrap()
+            .contains("int x;")
+    );
+    assert_eq!(
+        fs::metadata(java_dir.join("Main.java"))
+            .unwrap()
+            .modified()
+            .unwrap(),
+        main_modified
+    );
+
+    // Removing a whole package removes its directory as well
+    fs::remove_dir_all(test_dir.join("src/util")).unwrap();
+    build();
+    assert!(!java_dir.join("util").exists());
+
+    let _ = fs::remove_dir_all(&test_dir);
+}
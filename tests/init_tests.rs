@@ -0,0 +1,169 @@
+mod common;
+
+use serial_test::serial;
+use std::fs;
+use std::path::Path;
+
+// Major version of a class file, e.g. 55 for Java 11
+fn class_version(path: &Path) -> u16 {
+    let bytes = fs::read(path).unwrap();
+    u16::from_be_bytes([bytes[6], bytes[7]])
+}
+
+// Test that `rsj new` scaffolds a project from the given options
+#[test]
+#[serial]
+fn test_new_with_options() {
+    let test_dir = common::create_test_project("init_new_test", &[]).unwrap();
+    fs::create_dir_all(&test_dir).unwrap();
+
+    let output = common::rsj_output(
+        &test_dir,
+        &[
+            "new",
+            "tool",
+            "--name",
+            "acme-tool",
+            "--namespace",
+            "org.acme",
+            "--main-class",
+            "app.Launcher",
+            "--java-version",
+            "11",
+            "--vcs",
+            "none",
+        ],
+    )
+    .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stdout)
+    );
+
+    let project_dir = test_dir.join("tool");
+    let manifest = fs::read_to_string(project_dir.join("rsj.toml")).unwrap();
+    assert!(manifest.contains("name = \"acme-tool\"\n"), "{}", manifest);
+    assert!(manifest.contains("main_class = \"app.Launcher\"\n"));
+    assert!(manifest.contains("base_namespace = \"org.acme\"\n"));
+    assert!(manifest.contains("java_version = \"11\"\n"));
+    let launcher = fs::read_to_string(project_dir.join("src/app/Launcher.java")).unwrap();
+    assert!(
+        launcher.starts_with("package org.acme.app;"),
+        "{}",
+        launcher
+    );
+    assert_eq!(
+        fs::read_to_string(project_dir.join(".gitignore")).unwrap(),
+        "/rsj_build/\n"
+    );
+    assert!(!project_dir.join(".git").exists());
+
+    // The scaffold builds and runs, compiled for the requested release
+    fs::write(
+        project_dir.join("rsj.toml"),
+        manifest.replace(
+            "base_namespace = \"org.acme\"\n",
+            "base_namespace = \"org.acme\"\nbackend = \"javac\"\n",
+        ),
+    )
+    .unwrap();
+    let output = common::rsj_output(&project_dir, &["run"]).unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{}", stdout);
+    assert!(
+        stdout.contains("ClassOne method\nClassTwo method\n"),
+        "{}",
+        stdout
+    );
+    assert_eq!(
+        class_version(&project_dir.join("rsj_build/javac/classes/org/acme/app/Launcher.class")),
+        55
+    );
+
+    // The destination must not exist yet
+    let output = common::rsj_output(&test_dir, &["new", "tool", "--vcs", "none"]).unwrap();
+    assert_eq!(output.status.code(), Some(5));
+}
+
+// Test the defaults taken from the directory and the git configuration
+#[test]
+#[serial]
+fn test_init_defaults_from_git() {
+    let scratch = tempfile::tempdir().unwrap();
+    let project_dir = scratch.path().join("greeter-app");
+    fs::create_dir_all(&project_dir).unwrap();
+    fs::write(project_dir.join(".gitignore"), "*.log").unwrap();
+    let git_config = scratch.path().join("gitconfig");
+    fs::write(&git_config, "[user]\n\temail = jane@tools.acme-corp.io\n").unwrap();
+
+    let output = common::rsj_command(&project_dir, &["init"])
+        .unwrap()
+        .env("GIT_CONFIG_GLOBAL", &git_config)
+        .env("GIT_CONFIG_NOSYSTEM", "1")
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stdout)
+    );
+
+    let manifest = fs::read_to_string(project_dir.join("rsj.toml")).unwrap();
+    assert!(
+        manifest.contains("name = \"greeter-app\"\n"),
+        "{}",
+        manifest
+    );
+    assert!(
+        manifest.contains("base_namespace = \"io.acme_corp.tools\"\n"),
+        "{}",
+        manifest
+    );
+    assert!(manifest.contains("main_class = \"Main\"\n"));
+    assert!(!manifest.contains("java_version"));
+    assert_eq!(
+        fs::read_to_string(project_dir.join(".gitignore")).unwrap(),
+        "*.log\n/rsj_build/\n"
+    );
+    assert!(project_dir.join(".git").is_dir());
+
+    // Webmail domains fall back to `com.example`
+    let library_dir = scratch.path().join("lib");
+    fs::create_dir_all(&library_dir).unwrap();
+    fs::write(&git_config, "[user]\n\temail = jane@gmail.com\n").unwrap();
+    let output = common::rsj_command(&library_dir, &["init", "--lib"])
+        .unwrap()
+        .env("GIT_CONFIG_GLOBAL", &git_config)
+        .env("GIT_CONFIG_NOSYSTEM", "1")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let manifest = fs::read_to_string(library_dir.join("rsj.toml")).unwrap();
+    assert!(manifest.contains("base_namespace = \"com.example\"\n"));
+}
+
+// Test that invalid options fail before anything is written
+#[test]
+#[serial]
+fn test_init_rejects_invalid_options() {
+    let test_dir = common::create_test_project("init_invalid_test", &[]).unwrap();
+    fs::create_dir_all(&test_dir).unwrap();
+
+    for args in [
+        &["init", "--namespace", "com.class"][..],
+        &["init", "--main-class", "my-main"],
+        &["init", "--java-version", "seventeen"],
+        &["init", "--name", "a/b"],
+    ] {
+        let output = common::rsj_output(&test_dir, args).unwrap();
+        assert_eq!(
+            output.status.code(),
+            Some(2),
+            "{:?}: {}",
+            args,
+            String::from_utf8_lossy(&output.stdout)
+        );
+        assert!(!test_dir.join("rsj.toml").exists());
+    }
+}
@@ -0,0 +1,178 @@
+mod common;
+
+use serial_test::serial;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+const MAIN_JAVA: &str = r#"import com.acme.Greeter;
+
+public class Main {
+    public static void main(String[] args) {
+        System.out.println(Greeter.greet("javac"));
+    }
+}
+"#;
+
+// Build a `com.acme:greeter:1.0` JAR into a Maven-layout local repository
+fn create_local_repository(test_dir: &Path) {
+    let work = test_dir.join("greeter");
+    fs::create_dir_all(work.join("com/acme")).unwrap();
+    fs::write(
+        work.join("com/acme/Greeter.java"),
+        "package com.acme;\n\npublic class Greeter {\n    public static String greet(String name) {\n        return \"Hello from \" + name + \"!\";\n    }\n}\n",
+    )
+    .unwrap();
+    common::run_command_in_dir(&work, "javac", &["-d", "classes", "com/acme/Greeter.java"])
+        .unwrap();
+
+    let repo_dir = test_dir.join("repo/com/acme/greeter/1.0");
+    fs::create_dir_all(&repo_dir).unwrap();
+    common::run_command_in_dir(
+        &work,
+        "jar",
+        &[
+            "--create",
+            "--file",
+            repo_dir.join("greeter-1.0.jar").to_str().unwrap(),
+            "-C",
+            "classes",
+            ".",
+        ],
+    )
+    .unwrap();
+}
+
+fn rsj_toml(use_shadow: bool) -> String {
+    format!(
+        r#"[project]
+name = "javac_app"
+version = "1.0.0"
+main_class = "Main"
+base_namespace = "com.example"
+backend = "javac"
+local_repository = "repo"
+use_shadow = {}
+
+[dependencies]
+greeter = "com.acme:greeter:1.0"
+"#,
+        use_shadow
+    )
+}
+
+// Test that the javac backend builds a runnable fat JAR without Gradle
+#[test]
+#[serial]
+fn test_javac_backend_fat_jar() {
+    let toml = rsj_toml(true);
+    let test_dir = common::create_test_project(
+        "javac_fat_jar_test",
+        &[("rsj.toml", &toml), ("src/Main.java", MAIN_JAVA)],
+    )
+    .unwrap();
+    create_local_repository(&test_dir);
+
+    let output = common::rsj_output(&test_dir, &["run"]).unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{}", stdout);
+    assert!(stdout.contains("Hello from javac!"), "{}", stdout);
+    assert!(!test_dir.join("rsj_build/gradle").exists());
+
+    // The dependency is inside the JAR, so it runs on its own
+    let jar = test_dir.join("rsj_build/javac/libs/javac_app-1.0.0.jar");
+    let java = Command::new("java").arg("-jar").arg(&jar).output().unwrap();
+    assert_eq!(
+        String::from_utf8_lossy(&java.stdout).trim(),
+        "Hello from javac!"
+    );
+
+    // A second build has nothing to do
+    let output = common::rsj_output(&test_dir, &["build"]).unwrap();
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Build is up to date."));
+
+    let _ = fs::remove_dir_all(&test_dir);
+}
+
+// Test that without ShadowJar the dependencies are referenced from the manifest
+#[test]
+#[serial]
+fn test_javac_backend_thin_jar() {
+    let toml = rsj_toml(false);
+    let test_dir = common::create_test_project(
+        "javac_thin_jar_test",
+        &[("rsj.toml", &toml), ("src/Main.java", MAIN_JAVA)],
+    )
+    .unwrap();
+    create_local_repository(&test_dir);
+
+    let output = common::rsj_output(&test_dir, &["run"]).unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Hello from javac!"), "{}", stdout);
+    assert!(
+        test_dir
+            .join("rsj_build/javac/libs/lib/greeter-1.0.jar")
+            .exists()
+    );
+
+    let _ = fs::remove_dir_all(&test_dir);
+}
+
+// Test that a dependency missing from every local repository is reported
+#[test]
+#[serial]
+fn test_javac_backend_missing_dependency() {
+    let toml = rsj_toml(true);
+    let test_dir = common::create_test_project(
+        "javac_missing_dependency_test",
+        &[("rsj.toml", &toml), ("src/Main.java", MAIN_JAVA)],
+    )
+    .unwrap();
+
+    let output = common::rsj_output(&test_dir, &["build"]).unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(
+        stdout.contains("Dependency `greeter` (com.acme:greeter:1.0) was not found"),
+        "{}",
+        stdout
+    );
+
+    let _ = fs::remove_dir_all(&test_dir);
+}
+
+// Test that sources in a top-level `build` package are part of the fingerprint
+#[test]
+#[serial]
+fn test_javac_backend_rebuilds_build_package() {
+    let rsj_toml = r#"[project]
+name = "javac_build_package"
+version = "1.0.0"
+main_class = "Main"
+base_namespace = "build"
+backend = "javac"
+"#;
+    let main = |text: &str| {
+        format!(
+            "public class Main {{\n    public static void main(String[] args) {{\n        System.out.println(\"{}\");\n    }}\n}}\n",
+            text
+        )
+    };
+    let test_dir = common::create_test_project(
+        "javac_build_package_test",
+        &[("rsj.toml", rsj_toml), ("src/Main.java", &main("first"))],
+    )
+    .unwrap();
+
+    let output = common::rsj_output(&test_dir, &["run"]).unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("first"), "{}", stdout);
+
+    fs::write(test_dir.join("src/Main.java"), main("second")).unwrap();
+    let output = common::rsj_output(&test_dir, &["run"]).unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!stdout.contains("Build is up to date."), "{}", stdout);
+    assert!(stdout.contains("second"), "{}", stdout);
+
+    let _ = fs::remove_dir_all(&test_dir);
+}
@@ -0,0 +1,163 @@
+mod common;
+
+use serial_test::serial;
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+use std::process::Command;
+
+// The entries of a JAR, as listed by the `jar` tool
+fn jar_entries(jar: &Path) -> String {
+    let output = Command::new("jar")
+        .arg("--list")
+        .arg("--file")
+        .arg(jar)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "cannot list {}", jar.display());
+    String::from_utf8_lossy(&output.stdout).to_string()
+}
+
+fn jar_manifest(jar: &Path) -> String {
+    let mut archive = zip::ZipArchive::new(fs::File::open(jar).unwrap()).unwrap();
+    let mut manifest = String::new();
+    archive
+        .by_name("META-INF/MANIFEST.MF")
+        .unwrap()
+        .read_to_string(&mut manifest)
+        .unwrap();
+    manifest
+}
+
+// Test that `rsj init --lib` scaffolds a library that builds a plain JAR with
+// sources and Javadoc JARs
+#[test]
+#[serial]
+fn test_init_and_build_library() {
+    let test_dir = common::create_test_project("library_init_test", &[]).unwrap();
+    fs::create_dir_all(&test_dir).unwrap();
+
+    let output = common::rsj_output(
+        &test_dir,
+        &[
+            "init",
+            "--lib",
+            "--name",
+            "my_library",
+            "--namespace",
+            "com.example",
+            "--vcs",
+            "none",
+        ],
+    )
+    .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stdout)
+    );
+    let manifest = fs::read_to_string(test_dir.join("rsj.toml")).unwrap();
+    assert!(manifest.contains("[lib]"), "{}", manifest);
+    assert!(!manifest.contains("main_class"), "{}", manifest);
+    assert!(test_dir.join("src/Library.java").is_file());
+
+    fs::write(
+        test_dir.join("rsj.toml"),
+        manifest.replace(
+            "base_namespace = \"com.example\"\n",
+            "base_namespace = \"com.example\"\nbackend = \"javac\"\n",
+        ),
+    )
+    .unwrap();
+    let output = common::rsj_output(&test_dir, &["build"]).unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stdout)
+    );
+
+    let libs = test_dir.join("rsj_build/javac/libs");
+    let jar = libs.join("my_library-0.1.0.jar");
+    assert!(jar_entries(&jar).contains("com/example/Library.class"));
+    assert!(!jar_manifest(&jar).contains("Main-Class"));
+    assert!(
+        jar_entries(&libs.join("my_library-0.1.0-sources.jar"))
+            .contains("com/example/Library.java")
+    );
+    let javadoc = jar_entries(&libs.join("my_library-0.1.0-javadoc.jar"));
+    assert!(javadoc.contains("index.html"), "{}", javadoc);
+    assert!(javadoc.contains("com/example/Library.html"), "{}", javadoc);
+
+    // A library without binaries has nothing to run
+    let output = common::rsj_output(&test_dir, &["run"]).unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(output.status.code(), Some(2), "{}", stdout);
+    assert!(stdout.contains("has nothing to run"), "{}", stdout);
+}
+
+// Test that the Gradle build of a library has no application plugin or Main-Class
+#[test]
+#[serial]
+fn test_gradle_library() {
+    let toml = r#"[project]
+name = "gradle_library"
+version = "1.0.0"
+base_namespace = "com.example"
+
+[lib]
+javadoc = false
+"#;
+    let test_dir = common::create_test_project(
+        "library_gradle_test",
+        &[
+            ("rsj.toml", toml),
+            ("src/Library.java", "public class Library {}\n"),
+        ],
+    )
+    .unwrap();
+    let path = common::fake_tool_path(
+        &test_dir,
+        "gradle",
+        "#!/bin/sh\nmkdir -p build\n: > build/rsj-resolved.txt\n",
+    )
+    .unwrap();
+
+    let output = common::rsj_command(&test_dir, &["build"])
+        .unwrap()
+        .env("PATH", path)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stdout)
+    );
+
+    let build = fs::read_to_string(test_dir.join("rsj_build/gradle/build.gradle")).unwrap();
+    assert!(!build.contains("application"), "{}", build);
+    assert!(!build.contains("Main-Class"), "{}", build);
+    assert!(!build.contains("shadow"), "{}", build);
+    assert!(build.contains("withSourcesJar()"), "{}", build);
+    assert!(!build.contains("withJavadocJar()"), "{}", build);
+}
+
+// Test that applications still require `main_class` and the flags are exclusive
+#[test]
+#[serial]
+fn test_application_requires_main_class() {
+    let toml = r#"[project]
+name = "no_main"
+version = "1.0.0"
+base_namespace = "com.example"
+"#;
+    let test_dir =
+        common::create_test_project("library_missing_main_test", &[("rsj.toml", toml)]).unwrap();
+
+    let output = common::rsj_output(&test_dir, &["build"]).unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(output.status.code(), Some(2), "{}", stdout);
+    assert!(stdout.contains("missing `main_class`"), "{}", stdout);
+
+    let output = common::rsj_output(&test_dir, &["init", "--lib", "--bin"]).unwrap();
+    assert!(!output.status.success());
+}
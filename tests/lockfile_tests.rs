@@ -0,0 +1,257 @@
+mod common;
+
+use serial_test::serial;
+use std::fs;
+use std::path::Path;
+
+const MAIN_JAVA: &str = r#"public class Main {
+    public static void main(String[] args) {
+        System.out.println("locked");
+    }
+}
+"#;
+
+// Put a small `com.acme:<name>:<version>` JAR into the Maven-layout repository `repo`
+fn publish_jar(test_dir: &Path, name: &str, version: &str, content: &str) {
+    let repo_dir = test_dir.join(format!("repo/com/acme/{}/{}", name, version));
+    fs::create_dir_all(&repo_dir).unwrap();
+    let source_dir = test_dir.join(format!("jar_{}", name));
+    fs::create_dir_all(&source_dir).unwrap();
+    fs::write(source_dir.join("content.txt"), content).unwrap();
+    common::run_command_in_dir(
+        &source_dir,
+        "jar",
+        &[
+            "--create",
+            "--file",
+            repo_dir
+                .join(format!("{}-{}.jar", name, version))
+                .to_str()
+                .unwrap(),
+            "content.txt",
+        ],
+    )
+    .unwrap();
+}
+
+fn javac_toml(dependencies: &str) -> String {
+    format!(
+        r#"[project]
+name = "locked_app"
+version = "1.0.0"
+main_class = "Main"
+base_namespace = "com.example"
+backend = "javac"
+local_repository = "repo"
+
+[dependencies]
+{}
+"#,
+        dependencies
+    )
+}
+
+// Test that builds write rsj.lock and `--locked` rejects any drift from it
+#[test]
+#[serial]
+fn test_lockfile_written_and_enforced() {
+    let toml = javac_toml(r#"greeter = "com.acme:greeter:1.0""#);
+    let test_dir = common::create_test_project(
+        "lockfile_test",
+        &[("rsj.toml", &toml), ("src/Main.java", MAIN_JAVA)],
+    )
+    .unwrap();
+    publish_jar(&test_dir, "greeter", "1.0", "one");
+    publish_jar(&test_dir, "greeter", "2.0", "two");
+
+    // Without a lockfile `--locked` has nothing to enforce
+    let output = common::rsj_output(&test_dir, &["build", "--locked"]).unwrap();
+    assert_eq!(output.status.code(), Some(6));
+
+    let output = common::rsj_output(&test_dir, &["build"]).unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stdout)
+    );
+    let lock = fs::read_to_string(test_dir.join("rsj.lock")).unwrap();
+    assert!(
+        lock.contains("greeter = \"com.acme:greeter:1.0\""),
+        "{}",
+        lock
+    );
+    assert!(lock.contains("[[artifact]]"), "{}", lock);
+    assert!(lock.contains("version = \"1.0\""), "{}", lock);
+    assert!(lock.contains("checksum = \"sha256:"), "{}", lock);
+
+    let output = common::rsj_output(&test_dir, &["build", "--locked"]).unwrap();
+    assert!(output.status.success());
+
+    // Editing `[dependencies]` fails with `--locked` and updates the lock without it
+    let toml = javac_toml(r#"greeter = "com.acme:greeter:2.0""#);
+    fs::write(test_dir.join("rsj.toml"), &toml).unwrap();
+    let output = common::rsj_output(&test_dir, &["build", "--locked"]).unwrap();
+    assert_eq!(output.status.code(), Some(6));
+    assert_eq!(fs::read_to_string(test_dir.join("rsj.lock")).unwrap(), lock);
+
+    let output = common::rsj_output(&test_dir, &["build"]).unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{}", stdout);
+    assert!(
+        stdout.contains("Updating com.acme:greeter 1.0 -> 2.0"),
+        "{}",
+        stdout
+    );
+
+    // An artifact whose content changed since it was locked is always rejected
+    publish_jar(&test_dir, "greeter", "2.0", "tampered");
+    let output = common::rsj_output(&test_dir, &["build"]).unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(output.status.code(), Some(6), "{}", stdout);
+    assert!(
+        stdout.contains("Checksum of `com.acme:greeter:2.0`"),
+        "{}",
+        stdout
+    );
+}
+
+// Test that `--locked` also notices edits to `[dev-dependencies]` and `[build-dependencies]`
+#[test]
+#[serial]
+fn test_lockfile_tracks_dev_and_build_dependencies() {
+    let toml = |dev_version: &str| {
+        format!(
+            "{}\n[dev-dependencies]\nhelper = \"com.acme:helper:{}\"\n",
+            javac_toml(r#"greeter = "com.acme:greeter:1.0""#),
+            dev_version
+        )
+    };
+    let test_dir = common::create_test_project(
+        "lockfile_dev_dependencies_test",
+        &[("rsj.toml", &toml("1.0")), ("src/Main.java", MAIN_JAVA)],
+    )
+    .unwrap();
+    publish_jar(&test_dir, "greeter", "1.0", "one");
+    publish_jar(&test_dir, "helper", "1.0", "one");
+    publish_jar(&test_dir, "helper", "2.0", "two");
+
+    let output = common::rsj_output(&test_dir, &["build"]).unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stdout)
+    );
+    let lock = fs::read_to_string(test_dir.join("rsj.lock")).unwrap();
+    assert!(
+        lock.contains("[dev-dependencies]\nhelper = \"com.acme:helper:1.0\"\n"),
+        "{}",
+        lock
+    );
+    let output = common::rsj_output(&test_dir, &["build", "--locked"]).unwrap();
+    assert!(output.status.success());
+
+    fs::write(test_dir.join("rsj.toml"), toml("2.0")).unwrap();
+    let output = common::rsj_output(&test_dir, &["build", "--locked"]).unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(output.status.code(), Some(6), "{}", stdout);
+    assert!(
+        stdout.contains("because `[dev-dependencies]` changed"),
+        "{}",
+        stdout
+    );
+
+    let output = common::rsj_output(&test_dir, &["build"]).unwrap();
+    assert!(output.status.success());
+    let output = common::rsj_output(&test_dir, &["build", "--locked"]).unwrap();
+    assert!(output.status.success());
+
+    // `rsj update -p` knows the dev-dependency too
+    let output = common::rsj_output(&test_dir, &["update", "-p", "helper"]).unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stdout)
+    );
+}
+
+const MAVEN_TOML: &str = r#"[project]
+name = "locked_maven"
+version = "1.0.0"
+main_class = "Main"
+base_namespace = "com.example"
+backend = "maven"
+
+[dependencies]
+greeter = "com.acme:greeter:1.0"
+"#;
+
+// Reports `greeter` and its transitive `util` dependency as resolved
+const RESOLVING_MVN: &str = r#"#!/bin/sh
+set -e
+mkdir -p target
+touch target/locked_maven-1.0.0.jar
+cat > target/rsj-resolved.txt <<EOF
+
+The following files have been resolved:
+   com.acme:greeter:jar:1.0:compile:$FAKE_REPO/com/acme/greeter/1.0/greeter-1.0.jar -- module greeter
+   com.acme:util:jar:3.1:runtime:$FAKE_REPO/com/acme/util/3.1/util-3.1.jar
+
+EOF
+"#;
+
+// Test that transitive dependencies are locked and pinned, except by `rsj update`
+#[test]
+#[serial]
+fn test_lockfile_pins_transitive_dependencies() {
+    let test_dir = common::create_test_project(
+        "lockfile_maven_test",
+        &[("rsj.toml", MAVEN_TOML), ("src/Main.java", MAIN_JAVA)],
+    )
+    .unwrap();
+    publish_jar(&test_dir, "greeter", "1.0", "greeter");
+    publish_jar(&test_dir, "util", "3.1", "util");
+    let path = common::fake_tool_path(&test_dir, "mvn", RESOLVING_MVN).unwrap();
+    let repo = test_dir.join("repo").canonicalize().unwrap();
+    let pom_path = test_dir.join("rsj_build/maven/pom.xml");
+
+    let rsj = |args: &[&str]| {
+        common::rsj_command(&test_dir, args)
+            .unwrap()
+            .env("PATH", &path)
+            .env("FAKE_REPO", &repo)
+            .output()
+            .unwrap()
+    };
+
+    let output = rsj(&["build"]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stdout)
+    );
+    let lock = fs::read_to_string(test_dir.join("rsj.lock")).unwrap();
+    assert!(lock.contains("name = \"util\""), "{}", lock);
+    assert!(
+        !fs::read_to_string(&pom_path)
+            .unwrap()
+            .contains("<dependencyManagement>")
+    );
+
+    // The next build holds both artifacts at their locked versions
+    let output = rsj(&["build", "--locked"]);
+    assert!(output.status.success());
+    let pom = fs::read_to_string(&pom_path).unwrap();
+    assert!(pom.contains("<dependencyManagement>"), "{}", pom);
+    assert_eq!(pom.matches("<artifactId>greeter</artifactId>").count(), 2);
+    assert!(pom.contains("<artifactId>util</artifactId>\n        <version>3.1</version>"));
+
+    // Updating one dependency releases its pin and keeps the others
+    let output = rsj(&["update", "-p", "greeter"]);
+    assert!(output.status.success());
+    let pom = fs::read_to_string(&pom_path).unwrap();
+    assert!(pom.contains("<artifactId>util</artifactId>\n        <version>3.1</version>"));
+    assert_eq!(pom.matches("<artifactId>greeter</artifactId>").count(), 1);
+
+    let output = rsj(&["update", "-p", "missing"]);
+    assert_eq!(output.status.code(), Some(2));
+}
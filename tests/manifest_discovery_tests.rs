@@ -0,0 +1,91 @@
+mod common;
+
+use serial_test::serial;
+
+const RSJ_TOML: &str = r#"[project]
+name = "discovery"
+version = "1.0.0"
+main_class = "Main"
+base_namespace = "com.example"
+backend = "javac"
+"#;
+
+const MAIN_JAVA: &str = r#"import com.example.util.Util;
+
+public class Main {
+    public static void main(String[] args) {
+        System.out.println(Util.message());
+    }
+}
+"#;
+
+const UTIL_JAVA: &str = r#"public class Util {
+    public static String message() {
+        return "Found the manifest!";
+    }
+}
+"#;
+
+// Test that rsj finds rsj.toml in a parent directory and builds next to it
+#[test]
+#[serial]
+fn test_manifest_found_from_subdirectory() {
+    let test_dir = common::create_test_project(
+        "manifest_discovery_test",
+        &[
+            ("rsj.toml", RSJ_TOML),
+            ("src/Main.java", MAIN_JAVA),
+            ("src/util/Util.java", UTIL_JAVA),
+        ],
+    )
+    .unwrap();
+    let util_dir = test_dir.join("src/util");
+
+    let output = common::rsj_output(&util_dir, &["run"]).unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{}", stdout);
+    assert!(stdout.contains("Found the manifest!"), "{}", stdout);
+    assert!(test_dir.join("rsj_build").exists());
+    assert!(!util_dir.join("rsj_build").exists());
+
+    let output = common::rsj_output(&util_dir, &["clean"]).unwrap();
+    assert!(output.status.success());
+    assert!(!test_dir.join("rsj_build").exists());
+}
+
+// Test that `--manifest-path` selects a project regardless of the current directory
+#[test]
+#[serial]
+fn test_manifest_path_flag() {
+    let test_dir = common::create_test_project(
+        "manifest_path_test",
+        &[
+            ("rsj.toml", RSJ_TOML),
+            ("src/Main.java", MAIN_JAVA),
+            ("src/util/Util.java", UTIL_JAVA),
+        ],
+    )
+    .unwrap();
+    let parent = test_dir.parent().unwrap();
+
+    let output = common::rsj_output(
+        parent,
+        &["build", "--manifest-path", "manifest_path_test/rsj.toml"],
+    )
+    .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{}", stdout);
+    assert!(test_dir.join("rsj_build/javac/libs").exists());
+    assert!(!parent.join("rsj_build").exists());
+
+    let output = common::rsj_output(
+        parent,
+        &[
+            "--manifest-path",
+            "manifest_path_test/missing.toml",
+            "build",
+        ],
+    )
+    .unwrap();
+    assert_eq!(output.status.code(), Some(2));
+}
@@ -0,0 +1,149 @@
+mod common;
+
+use serial_test::serial;
+use std::fs;
+
+const RSJ_TOML: &str = r#"[project]
+name = "maven_app"
+version = "1.0.0"
+main_class = "Main"
+base_namespace = "com.example"
+backend = "maven"
+
+[dependencies]
+gson = "com.google.code.gson:gson:2.10.1"
+"#;
+
+const MAIN_JAVA: &str = r#"public class Main {
+    public static void main(String[] args) {
+        System.out.println("Hello from Maven!");
+    }
+}
+"#;
+
+// Minimal stand-in for Maven: compile with javac and package a runnable JAR
+const FAKE_MVN: &str = r#"#!/bin/sh
+set -e
+artifact=$(sed -n "s/^  <artifactId>\(.*\)<\/artifactId>/\1/p" pom.xml)
+version=$(sed -n "s/^  <version>\(.*\)<\/version>/\1/p" pom.xml)
+main=$(sed -n "s/^ *<mainClass>\(.*\)<\/mainClass>/\1/p" pom.xml | head -n 1)
+echo "$@" >> mvn_args.txt
+mkdir -p target/classes
+javac -d target/classes $(find src/main/java -name '*.java')
+jar --create --file "target/$artifact-$version.jar" --main-class "$main" -C target/classes .
+echo "The following files have been resolved:" > target/rsj-resolved.txt
+"#;
+
+// Maven logs every compiler error twice, in the build log and in the summary
+const FAILING_MVN: &str = r#"#!/bin/sh
+file="$PWD/src/main/java/com/example/Main.java"
+echo "[INFO] Compiling 1 source file"
+echo "[ERROR] $file:[3,9] cannot find symbol"
+echo "[INFO] BUILD FAILURE"
+echo "[ERROR] $file:[3,9] cannot find symbol"
+exit 1
+"#;
+
+// Test that the Maven backend generates a POM and runs the JAR it builds
+#[test]
+#[serial]
+fn test_maven_backend_build_and_run() {
+    let test_dir = common::create_test_project(
+        "maven_backend_test",
+        &[("rsj.toml", RSJ_TOML), ("src/Main.java", MAIN_JAVA)],
+    )
+    .unwrap();
+    let path = common::fake_tool_path(&test_dir, "mvn", FAKE_MVN).unwrap();
+
+    let output = common::rsj_command(&test_dir, &["run"])
+        .unwrap()
+        .env("PATH", path)
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{}", stdout);
+    assert!(stdout.contains("Hello from Maven!"), "{}", stdout);
+
+    let maven_dir = test_dir.join("rsj_build/maven");
+    let pom = fs::read_to_string(maven_dir.join("pom.xml")).unwrap();
+    assert!(pom.contains("<groupId>com.google.code.gson</groupId>"));
+    assert!(pom.contains("<artifactId>gson</artifactId>"));
+    assert!(pom.contains("<mainClass>com.example.Main</mainClass>"));
+    assert!(pom.contains("maven-shade-plugin"));
+    assert!(
+        maven_dir
+            .join("src/main/java/com/example/Main.java")
+            .exists()
+    );
+
+    let args = fs::read_to_string(maven_dir.join("mvn_args.txt")).unwrap();
+    assert_eq!(
+        args.trim(),
+        "-B --no-transfer-progress package -DskipTests \
+         org.apache.maven.plugins:maven-dependency-plugin:3.6.1:list \
+         -DoutputFile=target/rsj-resolved.txt -DoutputAbsoluteArtifactFilename=true \
+         -DincludeScope=runtime"
+    );
+}
+
+// Test that Maven's compiler errors are mapped back to `src/` and reported once
+#[test]
+#[serial]
+fn test_maven_backend_diagnostics() {
+    let test_dir = common::create_test_project(
+        "maven_diagnostics_test",
+        &[("rsj.toml", RSJ_TOML), ("src/Main.java", MAIN_JAVA)],
+    )
+    .unwrap();
+    let path = common::fake_tool_path(&test_dir, "mvn", FAILING_MVN).unwrap();
+
+    let output = common::rsj_command(&test_dir, &["build"])
+        .unwrap()
+        .env("PATH", path)
+        .env("NO_COLOR", "1")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(output.status.code(), Some(101), "{}", stdout);
+    assert_eq!(
+        stdout.matches("--> src/Main.java:3:9").count(),
+        1,
+        "{}",
+        stdout
+    );
+    assert!(stdout.contains("error: cannot find symbol"), "{}", stdout);
+    assert!(
+        stdout.contains("Build failed due to 1 compilation error"),
+        "{}",
+        stdout
+    );
+}
+
+// Test that an error Maven reports at column 0 is rendered without a panic
+#[test]
+#[serial]
+fn test_maven_backend_diagnostic_at_column_zero() {
+    let test_dir = common::create_test_project(
+        "maven_column_zero_test",
+        &[("rsj.toml", RSJ_TOML), ("src/Main.java", MAIN_JAVA)],
+    )
+    .unwrap();
+    let path = common::fake_tool_path(
+        &test_dir,
+        "mvn",
+        "#!/bin/sh\necho \"[ERROR] $PWD/src/main/java/com/example/Main.java:[3,0] cannot find symbol\"\nexit 1\n",
+    )
+    .unwrap();
+
+    let output = common::rsj_command(&test_dir, &["build"])
+        .unwrap()
+        .env("PATH", path)
+        .env("NO_COLOR", "1")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(output.status.code(), Some(101), "{}", stdout);
+    assert!(stdout.contains("--> src/Main.java:3:0"), "{}", stdout);
+
+    let _ = fs::remove_dir_all(&test_dir);
+}
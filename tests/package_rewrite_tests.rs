@@ -0,0 +1,82 @@
+mod common;
+
+use serial_test::serial;
+use std::fs;
+
+const RSJ_TOML: &str = r#"[project]
+name = "package_rewrite"
+version = "1.0.0"
+main_class = "Main"
+base_namespace = "com.example"
+"#;
+
+// Test that only the real package declaration is rewritten, and lines stay put
+#[test]
+#[serial]
+fn test_package_rewrite_skips_comments_and_annotations() {
+    let header = "/*\n * Licensed; see the package LICENSE file.\n */\n";
+    let util = format!(
+        "{}// package fake;\npackage\n    util;\n\npublic class Util {{\n    static String s = \"package x;\";\n}}\n",
+        header
+    );
+    let info = "/** Docs; with a package mention. */\n@Deprecated(since = \"1;2\")\n@SuppressWarnings({\"a)\", \"\"\"\n    package y;\n    \"\"\"})\npackage com.example.util;\n";
+    let test_dir = common::create_test_project(
+        "package_rewrite_test",
+        &[
+            ("rsj.toml", RSJ_TOML),
+            ("src/Main.java", "public class Main {}\n"),
+            ("src/util/Util.java", &util),
+            ("src/util/package-info.java", info),
+        ],
+    )
+    .unwrap();
+
+    let _ = common::rsj_output(&test_dir, &["build"]).unwrap();
+
+    let java_dir = test_dir.join("rsj_build/gradle/src/main/java/com/example");
+    let main = fs::read_to_string(java_dir.join("Main.java")).unwrap();
+    assert_eq!(main, "package com.example; public class Main {}\n");
+
+    let rewritten = fs::read_to_string(java_dir.join("util/Util.java")).unwrap();
+    assert_eq!(
+        rewritten,
+        format!(
+            "{}// package fake;\npackage com.example.util;\n\n\npublic class Util {{\n    static String s = \"package x;\";\n}}\n",
+            header
+        )
+    );
+    assert_eq!(rewritten.lines().count(), util.lines().count());
+
+    let rewritten_info = fs::read_to_string(java_dir.join("util/package-info.java")).unwrap();
+    assert_eq!(rewritten_info, info);
+
+    let _ = fs::remove_dir_all(&test_dir);
+}
+
+// Test that a package which disagrees with the directory is reported
+#[test]
+#[serial]
+fn test_package_mismatch_is_reported() {
+    let test_dir = common::create_test_project(
+        "package_mismatch_test",
+        &[
+            ("rsj.toml", RSJ_TOML),
+            ("src/Main.java", "public class Main {}\n"),
+            (
+                "src/util/Util.java",
+                "// Header\npackage com.other;\n\npublic class Util {}\n",
+            ),
+        ],
+    )
+    .unwrap();
+
+    let output = common::rsj_output(&test_dir, &["build"]).unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("Util.java:2` declares package `com.other`, but its directory implies `com.example.util`"),
+        "Unexpected output: {}",
+        stdout
+    );
+
+    let _ = fs::remove_dir_all(&test_dir);
+}
@@ -0,0 +1,220 @@
+mod common;
+
+use serial_test::serial;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+const MAIN_JAVA: &str = r#"package com.example;
+
+import java.util.ArrayList;
+import java.util.List;
+
+public class Main {
+    public static void main(String[] args) {
+        List names = new ArrayList();
+        names.add(System.getProperty("mode"));
+        System.out.println("mode " + names.get(0));
+    }
+}
+"#;
+
+const RSJ_TOML: &str = r#"[project]
+name = "profiles"
+version = "1.0.0"
+main_class = "Main"
+base_namespace = "com.example"
+backend = "javac"
+
+[profile.dev]
+lint = ["all"]
+werror = true
+
+[profile.release]
+parameters = true
+
+[profile.ci]
+inherits = "release"
+jvm_args = ["-Dmode=ci"]
+"#;
+
+// `javap -v` of the main class, showing debug and parameter information
+fn disassemble(test_dir: &Path) -> String {
+    let output = Command::new("javap")
+        .args(["-v", "-cp"])
+        .arg(test_dir.join("rsj_build/javac/classes"))
+        .arg("com.example.Main")
+        .output()
+        .unwrap();
+    String::from_utf8_lossy(&output.stdout).to_string()
+}
+
+// Test that the profiles pass their javac flags and JVM arguments
+#[test]
+#[serial]
+fn test_profile_settings() {
+    let test_dir = common::create_test_project(
+        "profile_settings_test",
+        &[("rsj.toml", RSJ_TOML), ("src/Main.java", MAIN_JAVA)],
+    )
+    .unwrap();
+
+    // The raw `List` is a warning, which `werror` turns into a failure
+    let output = common::rsj_output(&test_dir, &["build"]).unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(output.status.code(), Some(101), "{}", stdout);
+
+    let output = common::rsj_output(&test_dir, &["build", "--release"]).unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stdout)
+    );
+    let classes = disassemble(&test_dir);
+    assert!(classes.contains("MethodParameters"), "{}", classes);
+    assert!(!classes.contains("LineNumberTable"), "{}", classes);
+
+    // A custom profile builds like the one it inherits and adds JVM arguments
+    let output = common::rsj_output(&test_dir, &["run", "--profile", "ci"]).unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{}", stdout);
+    assert!(stdout.contains("mode ci"), "{}", stdout);
+    assert!(disassemble(&test_dir).contains("MethodParameters"));
+
+    let output = common::rsj_output(&test_dir, &["build", "--profile", "bench"]).unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(output.status.code(), Some(2), "{}", stdout);
+    assert!(stdout.contains("Use one of dev, release, ci"), "{}", stdout);
+
+    let output = common::rsj_output(&test_dir, &["build", "--release", "--profile", "ci"]).unwrap();
+    assert!(!output.status.success());
+}
+
+// Test that Gradle and Maven get the profile's compiler arguments and JAR toggles
+#[test]
+#[serial]
+fn test_profile_in_generated_builds() {
+    let rsj_toml = r#"[project]
+name = "profile_builds"
+version = "1.0.0"
+base_namespace = "com.example"
+
+[lib]
+
+[profile.release]
+lint = ["all", "-serial"]
+parameters = true
+javadoc = false
+"#;
+    let test_dir = common::create_test_project(
+        "profile_builds_test",
+        &[
+            ("rsj.toml", rsj_toml),
+            ("src/Library.java", "public class Library {}\n"),
+        ],
+    )
+    .unwrap();
+    let path = common::fake_tool_path(
+        &test_dir,
+        "gradle",
+        "#!/bin/sh\nmkdir -p build\n: > build/rsj-resolved.txt\n",
+    )
+    .unwrap();
+    let build = |args: &[&str]| {
+        let output = common::rsj_command(&test_dir, args)
+            .unwrap()
+            .env("PATH", &path)
+            .output()
+            .unwrap();
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stdout)
+        );
+        fs::read_to_string(test_dir.join("rsj_build/gradle/build.gradle")).unwrap()
+    };
+
+    let dev = build(&["build"]);
+    assert!(!dev.contains("compilerArgs"), "{}", dev);
+    assert!(dev.contains("withJavadocJar()"), "{}", dev);
+
+    let release = build(&["build", "--release"]);
+    assert!(
+        release.contains("options.debug = false\n    options.compilerArgs += ['-Xlint:all,-serial', '-parameters']\n"),
+        "{}",
+        release
+    );
+    assert!(release.contains("withSourcesJar()"), "{}", release);
+    assert!(!release.contains("withJavadocJar()"), "{}", release);
+
+    // Maven gets the same arguments through the compiler plugin
+    fs::write(
+        test_dir.join("rsj.toml"),
+        rsj_toml.replace(
+            "base_namespace = \"com.example\"\n",
+            "base_namespace = \"com.example\"\nbackend = \"maven\"\n",
+        ),
+    )
+    .unwrap();
+    let path = common::fake_tool_path(&test_dir, "mvn", "#!/bin/sh\nexit 1\n").unwrap();
+    common::rsj_command(&test_dir, &["build", "--release"])
+        .unwrap()
+        .env("PATH", path)
+        .output()
+        .unwrap();
+    let pom = fs::read_to_string(test_dir.join("rsj_build/maven/pom.xml")).unwrap();
+    assert!(
+        pom.contains("<compilerArgs>\n            <arg>-Xlint:all,-serial</arg>\n            <arg>-parameters</arg>\n          </compilerArgs>"),
+        "{}",
+        pom
+    );
+    assert!(!pom.contains("maven-javadoc-plugin"), "{}", pom);
+}
+
+// Test that custom profiles must inherit from a known profile
+#[test]
+#[serial]
+fn test_invalid_profiles() {
+    let rsj_toml = r#"[project]
+name = "bad_profiles"
+version = "1.0.0"
+main_class = "Main"
+base_namespace = "com.example"
+
+[profile.release]
+inherits = "dev"
+
+[profile.bench]
+debug = false
+
+[profile.a]
+inherits = "b"
+
+[profile.b]
+inherits = "a"
+"#;
+    let test_dir = common::create_test_project(
+        "profile_invalid_test",
+        &[("rsj.toml", rsj_toml), ("src/Main.java", MAIN_JAVA)],
+    )
+    .unwrap();
+
+    let output = common::rsj_output(&test_dir, &["build"]).unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(output.status.code(), Some(2), "{}", stdout);
+    assert!(
+        stdout.contains("the built-in profile `release` cannot inherit"),
+        "{}",
+        stdout
+    );
+    assert!(
+        stdout.contains("profile `bench` needs `inherits`"),
+        "{}",
+        stdout
+    );
+    assert!(
+        stdout.contains("the `inherits` chain of `a` loops back at `a`"),
+        "{}",
+        stdout
+    );
+}
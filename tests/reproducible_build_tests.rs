@@ -0,0 +1,94 @@
+mod common;
+
+use serial_test::serial;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+const RSJ_TOML: &str = r#"[project]
+name = "reproducible"
+version = "1.0.0"
+main_class = "Main"
+base_namespace = "com.example"
+
+[dependencies]
+zeta = "org.example:zeta:1.0"
+alpha = "org.example:alpha:1.0"
+mu = "org.example:mu:1.0"
+beta = { group = "org.example", version = "1.0", scope = "api" }
+omega = "org.example:omega:1.0"
+gamma = "org.example:gamma:1.0"
+kappa = "org.example:kappa:1.0"
+delta = "org.example:delta:1.0"
+
+[dev-dependencies]
+yankee = "org.example:yankee:1.0"
+xray = "org.example:xray:1.0"
+"#;
+
+const MAIN_JAVA: &str = r#"public class Main {
+    public static void main(String[] args) {
+        System.out.println("same every time");
+    }
+}
+"#;
+
+// Every file of the generated Gradle project except Gradle's own output
+fn snapshot(gradle_dir: &Path) -> BTreeMap<PathBuf, Vec<u8>> {
+    WalkDir::new(gradle_dir)
+        .into_iter()
+        .filter_entry(|e| e.depth() != 1 || e.file_name() != "build")
+        .filter_map(Result::ok)
+        .filter(|e| e.file_type().is_file())
+        .map(|e| {
+            let relative = e.path().strip_prefix(gradle_dir).unwrap().to_path_buf();
+            (relative, fs::read(e.path()).unwrap())
+        })
+        .collect()
+}
+
+// Test that the generated Gradle project is identical across builds and lists
+// dependencies in declaration order
+#[test]
+#[serial]
+fn test_generated_gradle_project_is_reproducible() {
+    let test_dir = common::create_test_project(
+        "reproducible_build_test",
+        &[("rsj.toml", RSJ_TOML), ("src/Main.java", MAIN_JAVA)],
+    )
+    .unwrap();
+    let path = common::fake_gradle_path(&test_dir).unwrap();
+    let gradle_dir = test_dir.join("rsj_build/gradle");
+
+    let mut snapshots = Vec::new();
+    for _ in 0..2 {
+        let output = common::rsj_command(&test_dir, &["build"])
+            .unwrap()
+            .env("PATH", &path)
+            .output()
+            .unwrap();
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stdout)
+        );
+        snapshots.push(snapshot(&gradle_dir));
+        fs::remove_dir_all(test_dir.join("rsj_build")).unwrap();
+    }
+    assert!(!snapshots[0].is_empty());
+    assert_eq!(snapshots[0], snapshots[1]);
+
+    let build = String::from_utf8(snapshots[0][Path::new("build.gradle")].clone()).unwrap();
+    let order: Vec<&str> = build
+        .lines()
+        .filter_map(|line| line.split("org.example:").nth(1))
+        .map(|rest| rest.split(':').next().unwrap())
+        .collect();
+    assert_eq!(
+        order,
+        [
+            "zeta", "alpha", "mu", "beta", "omega", "gamma", "kappa", "delta", "yankee", "xray"
+        ]
+    );
+}
@@ -0,0 +1,157 @@
+mod common;
+
+use serial_test::serial;
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+
+fn jar_entry(jar: &Path, name: &str) -> Option<String> {
+    let mut archive = zip::ZipArchive::new(fs::File::open(jar).unwrap()).unwrap();
+    let mut entry = archive.by_name(name).ok()?;
+    let mut content = String::new();
+    entry.read_to_string(&mut content).unwrap();
+    Some(content)
+}
+
+const SERVICE: &str = "META-INF/services/com.example.Greeter";
+
+fn resources_project(name: &str, backend: &str) -> std::path::PathBuf {
+    let toml = format!(
+        r#"[project]
+name = "{}"
+version = "2.3.4"
+main_class = "Main"
+base_namespace = "com.example"
+backend = "{}"
+
+[resources]
+filter = ["*.properties"]
+properties = {{ greeting = "Hello" }}
+"#,
+        name, backend
+    );
+    let main = r#"package com.example;
+
+import java.io.InputStream;
+import java.util.Properties;
+
+public class Main {
+    public static void main(String[] args) throws Exception {
+        Properties properties = new Properties();
+        try (InputStream in = Main.class.getResourceAsStream("/app.properties")) {
+            properties.load(in);
+        }
+        System.out.println(properties.getProperty("greeting") + " " + properties.getProperty("version"));
+    }
+}
+"#;
+    let service_path = format!("resources/{}", SERVICE);
+    common::create_test_project(
+        name,
+        &[
+            ("rsj.toml", toml.as_str()),
+            ("src/Main.java", main),
+            (
+                "resources/app.properties",
+                "version=${project.version}\ngreeting=${greeting}\nother=${unknown.key}\n",
+            ),
+            (service_path.as_str(), "com.example.EnglishGreeter\n"),
+            ("resources/config/raw.txt", "${project.version}\n"),
+            ("test-resources/fixture.json", "{}\n"),
+        ],
+    )
+    .unwrap()
+}
+
+// Test that the javac backend packs filtered resources into the JAR
+#[test]
+#[serial]
+fn test_javac_resources() {
+    let test_dir = resources_project("resources_javac_test", "javac");
+
+    let output = common::rsj_output(&test_dir, &["run"]).unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{}", stdout);
+    assert!(stdout.contains("Hello 2.3.4"), "{}", stdout);
+
+    let jar = test_dir.join("rsj_build/javac/libs/resources_javac_test-2.3.4.jar");
+    assert_eq!(
+        jar_entry(&jar, "app.properties").unwrap(),
+        "version=2.3.4\ngreeting=Hello\nother=${unknown.key}\n"
+    );
+    assert_eq!(
+        jar_entry(&jar, SERVICE).unwrap(),
+        "com.example.EnglishGreeter\n"
+    );
+    // Only the files the filter selects are filtered
+    assert_eq!(
+        jar_entry(&jar, "config/raw.txt").unwrap(),
+        "${project.version}\n"
+    );
+    assert!(jar_entry(&jar, "fixture.json").is_none());
+
+    // Removed resources leave the next JAR
+    fs::remove_file(test_dir.join("resources/config/raw.txt")).unwrap();
+    let output = common::rsj_output(&test_dir, &["build"]).unwrap();
+    assert!(output.status.success());
+    assert!(jar_entry(&jar, "config/raw.txt").is_none());
+}
+
+// Test that Gradle gets the resources in its standard layout
+#[test]
+#[serial]
+fn test_gradle_resources() {
+    let test_dir = resources_project("resources_gradle_test", "gradle");
+    let path = common::fake_tool_path(
+        &test_dir,
+        "gradle",
+        "#!/bin/sh\nmkdir -p build/libs\n: > build/rsj-resolved.txt\n: > build/libs/resources_gradle_test-2.3.4-all.jar\n",
+    )
+    .unwrap();
+
+    let output = common::rsj_command(&test_dir, &["build"])
+        .unwrap()
+        .env("PATH", path)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stdout)
+    );
+
+    let gradle_dir = test_dir.join("rsj_build/gradle");
+    assert_eq!(
+        fs::read_to_string(gradle_dir.join("src/main/resources/app.properties")).unwrap(),
+        "version=2.3.4\ngreeting=Hello\nother=${unknown.key}\n"
+    );
+    assert!(
+        gradle_dir
+            .join("src/main/resources")
+            .join(SERVICE)
+            .is_file()
+    );
+    assert!(gradle_dir.join("src/test/resources/fixture.json").is_file());
+}
+
+// Test that filters must be relative globs
+#[test]
+#[serial]
+fn test_invalid_filter() {
+    let toml = r#"[project]
+name = "bad_filter"
+version = "1.0.0"
+main_class = "Main"
+base_namespace = "com.example"
+
+[resources]
+filter = ["/etc/*.conf"]
+"#;
+    let test_dir =
+        common::create_test_project("resources_invalid_test", &[("rsj.toml", toml)]).unwrap();
+
+    let output = common::rsj_output(&test_dir, &["build"]).unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(output.status.code(), Some(2), "{}", stdout);
+    assert!(stdout.contains("invalid `filter`"), "{}", stdout);
+}
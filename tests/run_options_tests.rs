@@ -0,0 +1,135 @@
+mod common;
+
+use serial_test::serial;
+use std::fs;
+use std::process::Command;
+
+const RSJ_TOML: &str = r#"[project]
+name = "run_options"
+version = "1.0.0"
+main_class = "Main"
+base_namespace = "com.example"
+backend = "javac"
+
+[run]
+jvm_args = ["-Dorder=config", "-Xmx64m"]
+system_properties = { "app.mode" = "test" }
+env = { GREETING = "hello" }
+working_dir = "data"
+"#;
+
+const MAIN_JAVA: &str = r#"import java.nio.file.Files;
+import java.nio.file.Path;
+
+public class Main {
+    public static void main(String[] args) throws Exception {
+        System.out.println("args=" + String.join(",", args));
+        System.out.println("order=" + System.getProperty("order"));
+        System.out.println("mode=" + System.getProperty("app.mode"));
+        System.out.println("env=" + System.getenv("GREETING"));
+        System.out.println("file=" + Files.readString(Path.of("input.txt")).trim());
+        System.exit(args.length > 0 && args[0].equals("fail") ? 7 : 0);
+    }
+}
+"#;
+
+// Test that `[run]` and the command line configure the JVM and the program
+#[test]
+#[serial]
+fn test_run_options() {
+    let test_dir = common::create_test_project(
+        "run_options_test",
+        &[
+            ("rsj.toml", RSJ_TOML),
+            ("src/Main.java", MAIN_JAVA),
+            ("data/input.txt", "from data\n"),
+        ],
+    )
+    .unwrap();
+
+    let output = common::rsj_output(
+        &test_dir,
+        &["run", "--jvm-arg", "-Dorder=cli", "--", "one", "--two"],
+    )
+    .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{}", stdout);
+    for expected in [
+        "args=one,--two\n",
+        "order=cli\n",
+        "mode=test\n",
+        "env=hello\n",
+        "file=from data\n",
+    ] {
+        assert!(
+            stdout.contains(expected),
+            "missing {:?} in\n{}",
+            expected,
+            stdout
+        );
+    }
+
+    // The program's exit code is passed through
+    let output = common::rsj_output(&test_dir, &["run", "--", "fail"]).unwrap();
+    assert_eq!(output.status.code(), Some(7));
+
+    let _ = fs::remove_dir_all(&test_dir);
+}
+
+// Test that `--jvm-arg -D...` overrides a `[run] system_properties` entry
+#[test]
+#[serial]
+fn test_cli_overrides_system_properties() {
+    let test_dir = common::create_test_project(
+        "run_options_override_test",
+        &[
+            ("rsj.toml", RSJ_TOML),
+            ("src/Main.java", MAIN_JAVA),
+            ("data/input.txt", "from data\n"),
+        ],
+    )
+    .unwrap();
+
+    let output = common::rsj_output(&test_dir, &["run", "--jvm-arg", "-Dapp.mode=cli"]).unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{}", stdout);
+    assert!(stdout.contains("mode=cli\n"), "{}", stdout);
+
+    let _ = fs::remove_dir_all(&test_dir);
+}
+
+// Test that `--release` compiles without debug information
+#[test]
+#[serial]
+fn test_release_profile() {
+    let test_dir = common::create_test_project(
+        "release_profile_test",
+        &[
+            ("rsj.toml", RSJ_TOML),
+            ("src/Main.java", MAIN_JAVA),
+            ("data/input.txt", "from data\n"),
+        ],
+    )
+    .unwrap();
+    let classes = test_dir.join("rsj_build/javac/classes");
+    let line_numbers = || {
+        let output = Command::new("javap")
+            .args(["-l", "-cp"])
+            .arg(&classes)
+            .arg("com.example.Main")
+            .output()
+            .unwrap();
+        String::from_utf8_lossy(&output.stdout).contains("LineNumberTable")
+    };
+
+    let output = common::rsj_output(&test_dir, &["build"]).unwrap();
+    assert!(output.status.success());
+    assert!(line_numbers());
+
+    let output = common::rsj_output(&test_dir, &["build", "--release"]).unwrap();
+    assert!(output.status.success());
+    assert!(!line_numbers());
+    assert!(fs::metadata(classes.join("com/example/Main.class")).is_ok());
+
+    let _ = fs::remove_dir_all(&test_dir);
+}
@@ -0,0 +1,337 @@
+mod common;
+
+use serial_test::serial;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+const MAIN_JAVA: &str = r#"package com.example;
+
+import com.acme.Greeter;
+
+public class Main {
+    public static void main(String[] args) {
+        System.out.println(Greeter.greet("target"));
+    }
+}
+"#;
+
+fn rsj_toml(version: &str) -> String {
+    format!(
+        r#"[project]
+name = "layout"
+version = "{}"
+main_class = "Main"
+base_namespace = "com.example"
+backend = "javac"
+local_repository = "repo"
+use_shadow = false
+
+[dependencies]
+greeter = "com.acme:greeter:1.0"
+"#,
+        version
+    )
+}
+
+const GREETER_JAVA: &str = "package com.acme;\n\npublic class Greeter {\n    public static String greet(String name) {\n        return \"Hello from \" + name + \"!\";\n    }\n}\n";
+
+// Build `<group>:<artifact>:1.0` from one class into a Maven-layout local repository
+fn install_jar(test_dir: &Path, group: &str, artifact: &str, class: &str, source: &str) {
+    let work = test_dir.join("work").join(group).join(artifact);
+    let source_path = format!("{}.java", class.replace('.', "/"));
+    fs::create_dir_all(work.join(&source_path).parent().unwrap()).unwrap();
+    fs::write(work.join(&source_path), source).unwrap();
+    common::run_command_in_dir(&work, "javac", &["-d", "classes", &source_path]).unwrap();
+
+    let repo_dir = test_dir
+        .join("repo")
+        .join(group.replace('.', "/"))
+        .join(artifact)
+        .join("1.0");
+    fs::create_dir_all(&repo_dir).unwrap();
+    common::run_command_in_dir(
+        &work,
+        "jar",
+        &[
+            "--create",
+            "--file",
+            repo_dir
+                .join(format!("{}-1.0.jar", artifact))
+                .to_str()
+                .unwrap(),
+            "-C",
+            "classes",
+            ".",
+        ],
+    )
+    .unwrap();
+}
+
+// Test that `rsj build` collects the JAR, its dependencies and start scripts per profile
+#[test]
+#[serial]
+fn test_build_collects_artifacts() {
+    let test_dir = common::create_test_project(
+        "target_layout_test",
+        &[
+            ("rsj.toml", &rsj_toml("1.0.0")),
+            ("src/Main.java", MAIN_JAVA),
+        ],
+    )
+    .unwrap();
+    install_jar(
+        &test_dir,
+        "com.acme",
+        "greeter",
+        "com.acme.Greeter",
+        GREETER_JAVA,
+    );
+
+    let output = common::rsj_output(&test_dir, &["build", "--release"]).unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{}", stdout);
+    let release_dir = test_dir.join("target/release");
+    let jar = release_dir.join("layout-1.0.0.jar");
+    assert!(
+        stdout.contains("Build succeeded! Artifacts are in target/release\n"),
+        "{}",
+        stdout
+    );
+    assert!(
+        stdout.contains("  JAR        target/release/layout-1.0.0.jar\n"),
+        "{}",
+        stdout
+    );
+    assert!(stdout.contains("Libraries  1 in"), "{}", stdout);
+    assert!(jar.is_file());
+    assert!(release_dir.join("lib/greeter-1.0.jar").is_file());
+    assert_eq!(
+        fs::read_to_string(test_dir.join("target/.gitignore")).unwrap(),
+        "*\n"
+    );
+    assert!(!test_dir.join("target/dev").exists());
+
+    let manifest = fs::read_to_string(release_dir.join("artifacts.toml")).unwrap();
+    assert!(
+        manifest.contains(
+            "name = \"layout\"\nversion = \"1.0.0\"\nprofile = \"release\"\nbackend = \"javac\"\n"
+        ),
+        "{}",
+        manifest
+    );
+    assert!(
+        manifest.contains("[[artifact]]\nkind = \"jar\"\npath = \"layout-1.0.0.jar\"\nsha256 = \""),
+        "{}",
+        manifest
+    );
+    assert!(
+        manifest.contains("kind = \"dependency\"\npath = \"lib/greeter-1.0.jar\"\n"),
+        "{}",
+        manifest
+    );
+    assert!(
+        manifest.contains("kind = \"script\"\nbinary = \"layout\"\npath = \"bin/layout\"\n"),
+        "{}",
+        manifest
+    );
+
+    // The start script finds the JARs wherever the directory is moved to
+    let moved = test_dir.join("moved");
+    fs::rename(&release_dir, &moved).unwrap();
+    let output = Command::new(moved.join("bin/layout"))
+        .current_dir(std::env::temp_dir())
+        .output()
+        .unwrap();
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "Hello from target!\n"
+    );
+    let output = Command::new("java")
+        .arg("-jar")
+        .arg(moved.join("layout-1.0.0.jar"))
+        .output()
+        .unwrap();
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "Hello from target!\n"
+    );
+
+    // A new version replaces the old JAR
+    fs::write(test_dir.join("rsj.toml"), rsj_toml("1.1.0")).unwrap();
+    let output = common::rsj_output(&test_dir, &["run"]).unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Hello from target!"), "{}", stdout);
+    assert!(test_dir.join("target/dev/layout-1.1.0.jar").is_file());
+    fs::write(test_dir.join("rsj.toml"), rsj_toml("1.2.0")).unwrap();
+    let output = common::rsj_output(&test_dir, &["build"]).unwrap();
+    assert!(output.status.success());
+    assert!(test_dir.join("target/dev/layout-1.2.0.jar").is_file());
+    assert!(!test_dir.join("target/dev/layout-1.1.0.jar").exists());
+}
+
+// Test that dependencies sharing a file name both end up in `lib/`
+#[test]
+#[serial]
+fn test_dependency_file_name_clash() {
+    let rsj_toml = r#"[project]
+name = "layout_clash"
+version = "1.0.0"
+main_class = "Main"
+base_namespace = "com.example"
+backend = "javac"
+local_repository = "repo"
+use_shadow = false
+
+[dependencies]
+acme = "com.acme:core:1.0"
+other = "org.other:core:1.0"
+"#;
+    let main = r#"package com.example;
+
+public class Main {
+    public static void main(String[] args) {
+        System.out.println(com.acme.Greeter.greet("acme") + " " + org.other.Name.NAME);
+    }
+}
+"#;
+    let test_dir = common::create_test_project(
+        "target_layout_clash_test",
+        &[("rsj.toml", rsj_toml), ("src/Main.java", main)],
+    )
+    .unwrap();
+    install_jar(
+        &test_dir,
+        "com.acme",
+        "core",
+        "com.acme.Greeter",
+        GREETER_JAVA,
+    );
+    install_jar(
+        &test_dir,
+        "org.other",
+        "core",
+        "org.other.Name",
+        "package org.other;\n\npublic class Name {\n    public static final String NAME = \"other\";\n}\n",
+    );
+
+    let output = common::rsj_output(&test_dir, &["build"]).unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{}", stdout);
+    let lib_dir = test_dir.join("target/dev/lib");
+    assert!(lib_dir.join("com.acme-core-1.0.jar").is_file());
+    assert!(lib_dir.join("org.other-core-1.0.jar").is_file());
+    assert!(!lib_dir.join("core-1.0.jar").exists());
+
+    let output = Command::new(test_dir.join("target/dev/bin/layout_clash"))
+        .output()
+        .unwrap();
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "Hello from acme! other\n"
+    );
+
+    // The manifest's `Class-Path` names the renamed files too
+    let output = Command::new("java")
+        .arg("-jar")
+        .arg(test_dir.join("target/dev/layout_clash-1.0.0.jar"))
+        .output()
+        .unwrap();
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "Hello from acme! other\n",
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+// Test that libraries get their sources and Javadoc JARs next to the main JAR
+#[test]
+#[serial]
+fn test_library_artifacts() {
+    let rsj_toml = r#"[project]
+name = "layout_lib"
+version = "0.1.0"
+base_namespace = "com.example"
+backend = "javac"
+
+[lib]
+"#;
+    let test_dir = common::create_test_project(
+        "target_layout_lib_test",
+        &[
+            ("rsj.toml", rsj_toml),
+            (
+                "src/Library.java",
+                "/** A library. */\npublic class Library {}\n",
+            ),
+        ],
+    )
+    .unwrap();
+
+    let output = common::rsj_output(&test_dir, &["build"]).unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{}", stdout);
+    let dev_dir = test_dir.join("target/dev");
+    for name in [
+        "layout_lib-0.1.0.jar",
+        "layout_lib-0.1.0-sources.jar",
+        "layout_lib-0.1.0-javadoc.jar",
+    ] {
+        assert!(dev_dir.join(name).is_file(), "missing {}", name);
+        assert!(stdout.contains(name), "{}", stdout);
+    }
+    let manifest = fs::read_to_string(dev_dir.join("artifacts.toml")).unwrap();
+    assert!(manifest.contains("kind = \"sources\""), "{}", manifest);
+    assert!(manifest.contains("kind = \"javadoc\""), "{}", manifest);
+    assert!(!manifest.contains("kind = \"script\""), "{}", manifest);
+}
+
+// Test that `rsj clean` removes `target/`, but not one rsj did not create
+#[test]
+#[serial]
+fn test_clean_removes_target() {
+    let rsj_toml = r#"[project]
+name = "layout_clean"
+version = "1.0.0"
+main_class = "Main"
+base_namespace = "com.example"
+backend = "javac"
+"#;
+    let test_dir = common::create_test_project(
+        "target_layout_clean_test",
+        &[
+            ("rsj.toml", rsj_toml),
+            (
+                "src/Main.java",
+                "public class Main {\n    public static void main(String[] args) {}\n}\n",
+            ),
+        ],
+    )
+    .unwrap();
+
+    let output = common::rsj_output(&test_dir, &["build"]).unwrap();
+    assert!(output.status.success());
+    assert!(test_dir.join("target/dev/bin/layout_clean").is_file());
+    let output = common::rsj_output(&test_dir, &["clean"]).unwrap();
+    assert!(output.status.success());
+    assert!(!test_dir.join("target").exists());
+
+    fs::create_dir_all(test_dir.join("target/classes")).unwrap();
+    let output = common::rsj_output(&test_dir, &["clean"]).unwrap();
+    assert!(output.status.success());
+    assert!(test_dir.join("target/classes").exists());
+
+    // Building into another tool's `target/` neither takes it over nor rewrites its files
+    fs::write(test_dir.join("target/.gitignore"), "/classes\n").unwrap();
+    let output = common::rsj_output(&test_dir, &["build"]).unwrap();
+    assert!(output.status.success());
+    assert!(test_dir.join("target/dev/bin/layout_clean").is_file());
+    assert_eq!(
+        fs::read_to_string(test_dir.join("target/.gitignore")).unwrap(),
+        "/classes\n"
+    );
+    let output = common::rsj_output(&test_dir, &["clean"]).unwrap();
+    assert!(output.status.success());
+    assert!(test_dir.join("target/classes").exists());
+}
@@ -0,0 +1,177 @@
+mod common;
+
+use serial_test::serial;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+fn init(dir: &Path, template: &str) -> std::process::Output {
+    fs::create_dir_all(dir).unwrap();
+    common::rsj_output(
+        dir,
+        &[
+            "init",
+            "--template",
+            template,
+            "--name",
+            "demo",
+            "--namespace",
+            "org.acme",
+            "--vcs",
+            "none",
+        ],
+    )
+    .unwrap()
+}
+
+// Test that the built-in templates render their placeholders and build
+#[test]
+#[serial]
+fn test_builtin_templates() {
+    let test_dir = common::create_test_project("template_builtin_test", &[]).unwrap();
+
+    for template in ["cli", "lib", "picocli", "javafx", "http"] {
+        let project_dir = test_dir.join(template);
+        let output = init(&project_dir, template);
+        assert!(
+            output.status.success(),
+            "{}: {}",
+            template,
+            String::from_utf8_lossy(&output.stdout)
+        );
+        for entry in walkdir::WalkDir::new(&project_dir) {
+            let entry = entry.unwrap();
+            if entry.file_type().is_file() {
+                let content = fs::read_to_string(entry.path()).unwrap();
+                assert!(!content.contains("{{"), "{}", entry.path().display());
+            }
+        }
+    }
+
+    let manifest = fs::read_to_string(test_dir.join("picocli/rsj.toml")).unwrap();
+    assert!(manifest.contains("picocli = \"info.picocli:picocli:4.7.5\""));
+    let manifest = fs::read_to_string(test_dir.join("javafx/rsj.toml")).unwrap();
+    assert!(manifest.contains("classifier = \"linux"), "{}", manifest);
+    assert!(test_dir.join("javafx/src/ui/App.java").is_file());
+    let manifest = fs::read_to_string(test_dir.join("lib/rsj.toml")).unwrap();
+    assert!(manifest.contains("[lib]"));
+
+    // The dependency-free templates build as they are
+    for template in ["http", "lib"] {
+        let project_dir = test_dir.join(template);
+        let manifest = fs::read_to_string(project_dir.join("rsj.toml")).unwrap();
+        fs::write(
+            project_dir.join("rsj.toml"),
+            manifest.replace(
+                "base_namespace = \"org.acme\"\n",
+                "base_namespace = \"org.acme\"\nbackend = \"javac\"\n",
+            ),
+        )
+        .unwrap();
+        let output = common::rsj_output(&project_dir, &["build"]).unwrap();
+        assert!(
+            output.status.success(),
+            "{}: {}",
+            template,
+            String::from_utf8_lossy(&output.stdout)
+        );
+    }
+}
+
+fn create_user_template(dir: &Path) {
+    let files: &[(&str, &[u8])] = &[
+        (
+            "rsj.toml",
+            b"[project]\nname = \"{{name}}\"\nversion = \"{{version}}\"\nmain_class = \"{{main_class}}\"\nbase_namespace = \"{{namespace}}\"\n",
+        ),
+        (
+            "src/Main.java",
+            b"package {{namespace}};\n\npublic class Main {\n    static int[][] GRID = {{1}};\n\n    public static void main(String[] args) {\n        System.out.println(\"{{name}}\");\n    }\n}\n",
+        ),
+        ("docs/{{name}}.md", b"# {{ name }}\n"),
+        ("assets/logo.bin", &[0xff, 0xfe, b'{', b'{']),
+    ];
+    for (path, content) in files {
+        let path = dir.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+}
+
+fn assert_rendered(project_dir: &Path) {
+    let manifest = fs::read_to_string(project_dir.join("rsj.toml")).unwrap();
+    assert_eq!(
+        manifest,
+        "[project]\nname = \"demo\"\nversion = \"0.1.0\"\nmain_class = \"Main\"\nbase_namespace = \"org.acme\"\n"
+    );
+    let main = fs::read_to_string(project_dir.join("src/Main.java")).unwrap();
+    assert!(main.starts_with("package org.acme;"), "{}", main);
+    assert!(main.contains("GRID = {{1}};"), "{}", main);
+    assert!(main.contains("println(\"demo\")"), "{}", main);
+    assert_eq!(
+        fs::read_to_string(project_dir.join("docs/demo.md")).unwrap(),
+        "# demo\n"
+    );
+    assert_eq!(
+        fs::read(project_dir.join("assets/logo.bin")).unwrap(),
+        [0xff, 0xfe, b'{', b'{']
+    );
+    assert!(!project_dir.join(".git").exists());
+}
+
+// Test templates from a local directory and from a git repository
+#[test]
+#[serial]
+fn test_user_templates() {
+    let test_dir = common::create_test_project("template_user_test", &[]).unwrap();
+    let template_dir = test_dir.join("my-template");
+    create_user_template(&template_dir);
+
+    let output = init(&test_dir.join("from_dir"), template_dir.to_str().unwrap());
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stdout)
+    );
+    assert_rendered(&test_dir.join("from_dir"));
+
+    // The same files committed to a repository
+    let git = |args: &[&str]| {
+        let status = Command::new("git")
+            .args(["-c", "user.name=rsj", "-c", "user.email=rsj@example.com"])
+            .args(args)
+            .current_dir(&template_dir)
+            .status()
+            .unwrap();
+        assert!(status.success(), "git {:?}", args);
+    };
+    git(&["init", "--quiet"]);
+    git(&["add", "."]);
+    git(&["commit", "--quiet", "-m", "Template"]);
+    let url = format!("file://{}", template_dir.canonicalize().unwrap().display());
+
+    let output = init(&test_dir.join("from_git"), &url);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stdout)
+    );
+    assert_rendered(&test_dir.join("from_git"));
+}
+
+// Test that an unknown template lists the built-in ones
+#[test]
+#[serial]
+fn test_unknown_template() {
+    let test_dir = common::create_test_project("template_unknown_test", &[]).unwrap();
+
+    let output = init(&test_dir, "nope");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(output.status.code(), Some(2), "{}", stdout);
+    assert!(
+        stdout.contains("Use one of cli, lib, picocli, javafx, http"),
+        "{}",
+        stdout
+    );
+    assert!(!test_dir.join("rsj.toml").exists());
+}
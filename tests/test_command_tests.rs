@@ -0,0 +1,132 @@
+mod common;
+
+use serial_test::serial;
+use std::fs;
+
+const RSJ_TOML: &str = r#"[project]
+name = "test_command"
+version = "1.0.0"
+main_class = "Main"
+base_namespace = "com.example"
+
+[test]
+inline = true
+"#;
+
+// Stand-in for `gradle test` that records its arguments and writes a JUnit report
+const FAKE_GRADLE_TEST: &str = r#"#!/bin/sh
+echo "$@" > args.txt
+mkdir -p build/test-results/test
+cat > build/test-results/test/TEST-com.example.util.UtilTest.xml <<'XML'
+<?xml version="1.0" encoding="UTF-8"?>
+<testsuite name="com.example.util.UtilTest" tests="3" skipped="1" failures="1" errors="0">
+  <testcase name="adds()" classname="com.example.util.UtilTest" time="0.01"/>
+  <testcase name="fails()" classname="com.example.util.UtilTest" time="0.02">
+    <failure message="expected: &lt;1&gt; but was: &lt;2&gt;" type="AssertionFailedError">expected: &lt;1&gt; but was: &lt;2&gt;</failure>
+  </testcase>
+  <testcase name="skipped()" classname="com.example.util.UtilTest" time="0.0">
+    <skipped/>
+  </testcase>
+</testsuite>
+XML
+exit 1
+"#;
+
+// Test that tests are copied into the test source set and summarized like cargo
+#[test]
+#[serial]
+fn test_rsj_test_summary() {
+    let test_dir = common::create_test_project(
+        "test_command_test",
+        &[
+            ("rsj.toml", RSJ_TOML),
+            ("src/Main.java", "public class Main {}\n"),
+            ("src/MainTest.java", "public class MainTest {}\n"),
+            ("tests/util/UtilTest.java", "public class UtilTest {}\n"),
+        ],
+    )
+    .unwrap();
+
+    let path = common::fake_tool_path(&test_dir, "gradle", FAKE_GRADLE_TEST).unwrap();
+    let output = common::rsj_command(&test_dir, &["test", "Util", "--nocapture"])
+        .unwrap()
+        .env("PATH", path)
+        .env("NO_COLOR", "1")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    let gradle_dir = test_dir.join("rsj_build/gradle");
+    let args = fs::read_to_string(gradle_dir.join("args.txt")).unwrap();
+    assert_eq!(args.trim(), "test --tests *Util* -Pnocapture");
+
+    let test_java = gradle_dir.join("src/test/java/com/example");
+    let util_test = fs::read_to_string(test_java.join("util/UtilTest.java")).unwrap();
+    assert!(util_test.starts_with("package com.example.util;"));
+    assert!(test_java.join("MainTest.java").exists());
+    assert!(
+        !gradle_dir
+            .join("src/main/java/com/example/MainTest.java")
+            .exists()
+    );
+
+    assert!(stdout.contains("running 3 tests"), "{}", stdout);
+    assert!(stdout.contains("test com.example.util.UtilTest::adds ... ok"));
+    assert!(stdout.contains("test com.example.util.UtilTest::fails ... FAILED"));
+    assert!(stdout.contains("test com.example.util.UtilTest::skipped ... ignored"));
+    assert!(stdout.contains("expected: <1> but was: <2>"));
+    assert!(stdout.contains("test result: FAILED. 1 passed; 1 failed; 1 ignored"));
+    assert_eq!(output.status.code(), Some(100));
+
+    let _ = fs::remove_dir_all(&test_dir);
+}
+
+// Test that errors in inline tests point at `src/`, and those in `tests/` at `tests/`
+#[test]
+#[serial]
+fn test_rsj_test_diagnostics_point_at_inline_tests() {
+    let test_dir = common::create_test_project(
+        "test_command_diagnostics_test",
+        &[
+            ("rsj.toml", RSJ_TOML),
+            ("src/Main.java", "public class Main {}\n"),
+            ("src/MainTest.java", "public class MainTest { int x = ; }\n"),
+            (
+                "tests/util/UtilTest.java",
+                "public class UtilTest { int y = ; }\n",
+            ),
+        ],
+    )
+    .unwrap();
+
+    // javac's report on the generated copies
+    let path = common::fake_tool_path(
+        &test_dir,
+        "gradle",
+        r#"#!/bin/sh
+for file in MainTest util/UtilTest; do
+    path="$PWD/src/test/java/com/example/$file.java"
+    echo "$path:1: error: illegal start of expression" >&2
+    sed -n 1p "$path" >&2
+    echo "                                ^" >&2
+done
+exit 1
+"#,
+    )
+    .unwrap();
+    let output = common::rsj_command(&test_dir, &["test"])
+        .unwrap()
+        .env("PATH", path)
+        .env("NO_COLOR", "1")
+        .output()
+        .unwrap();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("--> src/MainTest.java:1:"), "{}", stderr);
+    assert!(
+        stderr.contains("--> tests/util/UtilTest.java:1:"),
+        "{}",
+        stderr
+    );
+
+    let _ = fs::remove_dir_all(&test_dir);
+}
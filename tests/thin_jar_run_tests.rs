@@ -0,0 +1,90 @@
+mod common;
+
+use serial_test::serial;
+use std::fs;
+
+const RSJ_TOML: &str = r#"[project]
+name = "thin"
+version = "1.0.0"
+main_class = "Main"
+base_namespace = "com.example"
+use_shadow = false
+
+[dependencies]
+greeter = "com.acme:greeter:1.0"
+"#;
+
+const MAIN_JAVA: &str = r#"public class Main {
+    public static void main(String[] args) {
+        System.out.println(com.acme.Greeter.greet(args[0]));
+    }
+}
+"#;
+
+// Stand-in for Gradle's `build` task: a plain JAR without a manifest, and the
+// dependency reported by `rsjResolve`
+const THIN_GRADLE: &str = r#"#!/bin/sh
+set -e
+if [ "$1" = wrapper ]; then
+    exit 1
+fi
+mkdir -p build/classes build/libs
+javac -cp "$GREETER_JAR" -d build/classes $(find src/main/java -name '*.java')
+jar --create --file build/libs/thin-1.0.0.jar -C build/classes .
+printf 'com.acme:greeter:1.0:\t%s\n' "$GREETER_JAR" > build/rsj-resolved.txt
+"#;
+
+// Test that `rsj run` puts resolved dependencies on the classpath of a plain JAR
+#[test]
+#[serial]
+fn test_run_without_shadow_jar() {
+    let test_dir = common::create_test_project(
+        "thin_jar_run_test",
+        &[
+            ("rsj.toml", RSJ_TOML),
+            ("src/Main.java", MAIN_JAVA),
+            (
+                "greeter/com/acme/Greeter.java",
+                "package com.acme;\n\npublic class Greeter {\n    public static String greet(String name) {\n        return \"Hello, \" + name + \"!\";\n    }\n}\n",
+            ),
+        ],
+    )
+    .unwrap();
+    let greeter_dir = test_dir.join("greeter");
+    common::run_command_in_dir(
+        &greeter_dir,
+        "javac",
+        &["-d", "classes", "com/acme/Greeter.java"],
+    )
+    .unwrap();
+    common::run_command_in_dir(
+        &greeter_dir,
+        "jar",
+        &[
+            "--create",
+            "--file",
+            "greeter-1.0.jar",
+            "-C",
+            "classes",
+            ".",
+        ],
+    )
+    .unwrap();
+    let greeter_jar = greeter_dir.join("greeter-1.0.jar").canonicalize().unwrap();
+    let path = common::fake_tool_path(&test_dir, "gradle", THIN_GRADLE).unwrap();
+
+    let output = common::rsj_command(&test_dir, &["run", "--", "thin"])
+        .unwrap()
+        .env("PATH", path)
+        .env("GREETER_JAR", &greeter_jar)
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{}", stdout);
+    assert!(stdout.contains("Hello, thin!"), "{}", stdout);
+    assert!(
+        fs::read_to_string(test_dir.join("rsj_build/gradle/build.gradle"))
+            .unwrap()
+            .contains("implementation 'com.acme:greeter:1.0'")
+    );
+}
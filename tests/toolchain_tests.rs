@@ -0,0 +1,263 @@
+mod common;
+
+use serial_test::serial;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const MAIN: &str = r#"package com.example;
+
+public class Main {
+    public static void main(String[] args) {
+        System.out.println("Hello from the toolchain");
+    }
+}
+"#;
+
+fn toml(name: &str, project: &str) -> String {
+    format!(
+        "[project]\nname = \"{}\"\nversion = \"1.0.0\"\nmain_class = \"Main\"\nbase_namespace = \"com.example\"\nbackend = \"javac\"\n{}",
+        name, project
+    )
+}
+
+// Install a JDK into an SDKMAN! directory whose programs log their use and hand
+// over to the real JDK
+fn fake_sdkman_jdk(test_dir: &Path, version: &str, vendor: &str) -> PathBuf {
+    let real_home = real_jdk_home();
+    let sdkman = test_dir.join("sdkman");
+    let home = sdkman
+        .join("candidates/java")
+        .join(format!("{}-fake", version));
+    fs::create_dir_all(home.join("bin")).unwrap();
+    fs::write(
+        home.join("release"),
+        format!("IMPLEMENTOR=\"{}\"\nJAVA_VERSION=\"{}\"\n", vendor, version),
+    )
+    .unwrap();
+    let log = test_dir.join("jdk.log");
+    for tool in ["java", "javac", "javadoc"] {
+        let script = home.join("bin").join(tool);
+        fs::write(
+            &script,
+            format!(
+                "#!/bin/sh\necho {} >> \"{}\"\nexec \"{}\" \"$@\"\n",
+                tool,
+                log.display(),
+                real_home.join("bin").join(tool).display()
+            ),
+        )
+        .unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+        }
+    }
+    sdkman
+}
+
+// The JDK running these tests, through the `javac` on PATH
+fn real_jdk_home() -> PathBuf {
+    let path = env::var_os("PATH").unwrap();
+    let javac = env::split_paths(&path)
+        .map(|dir| dir.join("javac"))
+        .find(|candidate| candidate.is_file())
+        .expect("javac on PATH");
+    let javac = javac.canonicalize().unwrap();
+    javac.parent().unwrap().parent().unwrap().to_path_buf()
+}
+
+// Test that `java_version` and `vendor` select the JDK to compile and run with
+#[test]
+#[serial]
+fn test_selects_jdk_by_vendor() {
+    let test_dir = common::create_test_project(
+        "toolchain_vendor_test",
+        &[
+            (
+                "rsj.toml",
+                &toml(
+                    "toolchain_vendor",
+                    "java_version = \"17\"\nvendor = \"acme\"\n",
+                ),
+            ),
+            ("src/Main.java", MAIN),
+        ],
+    )
+    .unwrap();
+    let sdkman = fake_sdkman_jdk(&test_dir, "17.0.99", "Acme Corp");
+
+    let output = common::rsj_command(&test_dir, &["run"])
+        .unwrap()
+        .env("SDKMAN_DIR", &sdkman)
+        .env_remove("JAVA_HOME")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{}", stdout);
+    assert!(stdout.contains("Hello from the toolchain"), "{}", stdout);
+    assert_eq!(
+        fs::read_to_string(test_dir.join("jdk.log")).unwrap(),
+        "javac\njava\n"
+    );
+}
+
+// Test that a missing JDK is reported with the installed ones
+#[test]
+#[serial]
+fn test_missing_jdk_lists_installed() {
+    let test_dir = common::create_test_project(
+        "toolchain_missing_test",
+        &[
+            (
+                "rsj.toml",
+                &toml("toolchain_missing", "java_version = \"99\"\n"),
+            ),
+            ("src/Main.java", MAIN),
+        ],
+    )
+    .unwrap();
+    let sdkman = fake_sdkman_jdk(&test_dir, "17.0.99", "Acme Corp");
+
+    let output = common::rsj_command(&test_dir, &["build"])
+        .unwrap()
+        .env("SDKMAN_DIR", &sdkman)
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(output.status.code(), Some(127), "{}", stdout);
+    assert!(stdout.contains("JDK 99 is required"), "{}", stdout);
+    assert!(stdout.contains("Installed JDKs:"), "{}", stdout);
+    assert!(
+        stdout.contains(&format!(
+            "17 (Acme Corp) {}",
+            sdkman.join("candidates/java/17.0.99-fake").display()
+        )),
+        "{}",
+        stdout
+    );
+    assert!(!test_dir.join("jdk.log").exists());
+}
+
+// Test that Gradle gets a toolchain instead of the JDK that runs it
+#[test]
+#[serial]
+fn test_gradle_toolchain() {
+    let rsj_toml = toml(
+        "toolchain_gradle",
+        "java_version = \"21\"\nvendor = \"adoptium\"\n",
+    )
+    .replace("backend = \"javac\"\n", "");
+    let test_dir = common::create_test_project(
+        "toolchain_gradle_test",
+        &[("rsj.toml", &rsj_toml), ("src/Main.java", MAIN)],
+    )
+    .unwrap();
+    let path = common::fake_tool_path(
+        &test_dir,
+        "gradle",
+        "#!/bin/sh\nmkdir -p build/libs\n: > build/rsj-resolved.txt\n: > build/libs/toolchain_gradle-1.0.0-all.jar\n",
+    )
+    .unwrap();
+
+    let output = common::rsj_command(&test_dir, &["build"])
+        .unwrap()
+        .env("PATH", path)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stdout)
+    );
+
+    let build = fs::read_to_string(test_dir.join("rsj_build/gradle/build.gradle")).unwrap();
+    assert!(
+        build.contains(
+            "java {\n    toolchain {\n        languageVersion = JavaLanguageVersion.of(21)\n        vendor = JvmVendorSpec.matching('adoptium')\n    }\n}\n"
+        ),
+        "{}",
+        build
+    );
+}
+
+// Test that Gradle compiles with a newer installed JDK when none of `java_version` is
+// installed, like the javac backend
+#[test]
+#[serial]
+fn test_gradle_toolchain_newer_jdk() {
+    let rsj_toml = toml("toolchain_gradle_newer", "java_version = \"11\"\n")
+        .replace("backend = \"javac\"\n", "");
+    let test_dir = common::create_test_project(
+        "toolchain_gradle_newer_test",
+        &[("rsj.toml", &rsj_toml), ("src/Main.java", MAIN)],
+    )
+    .unwrap();
+    let sdkman = fake_sdkman_jdk(&test_dir, "17.0.99", "Acme Corp");
+    let path = common::fake_tool_path(
+        &test_dir,
+        "gradle",
+        "#!/bin/sh\nmkdir -p build/libs\n: > build/rsj-resolved.txt\n: > build/libs/toolchain_gradle_newer-1.0.0.jar\n",
+    )
+    .unwrap();
+
+    let output = common::rsj_command(&test_dir, &["build"])
+        .unwrap()
+        .env("PATH", path)
+        .env("SDKMAN_DIR", &sdkman)
+        .env("JAVA_HOME", sdkman.join("candidates/java/17.0.99-fake"))
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stdout)
+    );
+
+    let gradle_dir = test_dir.join("rsj_build/gradle");
+    let build = fs::read_to_string(gradle_dir.join("build.gradle")).unwrap();
+    assert!(
+        build
+            .contains("toolchain {\n        languageVersion = JavaLanguageVersion.of(17)\n    }\n"),
+        "{}",
+        build
+    );
+    assert!(
+        build.contains("tasks.withType(JavaCompile).configureEach {\n    options.release = 11\n"),
+        "{}",
+        build
+    );
+    let properties = fs::read_to_string(gradle_dir.join("gradle.properties")).unwrap();
+    assert!(
+        properties.contains(&format!(
+            "org.gradle.java.installations.paths={}",
+            sdkman.join("candidates/java/17.0.99-fake").display()
+        )),
+        "{}",
+        properties
+    );
+}
+
+// Test that `vendor` only applies together with `java_version`
+#[test]
+#[serial]
+fn test_vendor_requires_java_version() {
+    let test_dir = common::create_test_project(
+        "toolchain_vendor_only_test",
+        &[
+            ("rsj.toml", &toml("vendor_only", "vendor = \"acme\"\n")),
+            ("src/Main.java", MAIN),
+        ],
+    )
+    .unwrap();
+
+    let output = common::rsj_output(&test_dir, &["build"]).unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(output.status.code(), Some(2), "{}", stdout);
+    assert!(
+        stdout.contains("`vendor` requires `java_version`"),
+        "{}",
+        stdout
+    );
+}